@@ -7,6 +7,28 @@ pub trait IdentityInterface<Origin, AccountId, DispatchResult> {
     fn vouch_for(origin: Origin, other: AccountId) -> DispatchResult;
 
     fn get_voter_from_set(account_id: AccountId) -> Option<()>;
+
+    fn voter_count() -> u32;
+}
+
+/// Hook for pallets keeping state about voter set members, called by the identity pallet whenever an account leaves the
+/// voter set, whether it was removed or its membership expired.
+pub trait OnMemberRemoved<AccountId> {
+    /// Called once `who` is no longer in the voter set. Returns the weight used
+    fn on_member_removed(who: &AccountId) -> u64;
+
+    /// Most weight a single `on_member_removed` call can use
+    fn max_weight() -> u64;
+}
+
+impl<AccountId> OnMemberRemoved<AccountId> for () {
+    fn on_member_removed(_who: &AccountId) -> u64 {
+        0
+    }
+
+    fn max_weight() -> u64 {
+        0
+    }
 }
 
 /// Interface usable to expose kitties pallet functionality without requiring the kitties pallet itself as a dependency
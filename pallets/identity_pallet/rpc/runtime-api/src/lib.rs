@@ -0,0 +1,30 @@
+//! Runtime API definition for the identity pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use identity_pallet::MemberRecord;
+
+sp_api::decl_runtime_apis! {
+	pub trait IdentityGraphApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// A member's admission block and originator status
+		fn member(who: AccountId) -> Option<MemberRecord<BlockNumber>>;
+
+		/// The members whose vouches admitted an account
+		fn vouchers(who: AccountId) -> Vec<AccountId>;
+
+		/// The accounts a member has vouched for, pending or admitted
+		fn vouchees(who: AccountId) -> Vec<AccountId>;
+
+		/// Shortest chain of vouchers from a member back to an originator, starting with the member
+		fn path_to_originator(who: AccountId) -> Option<Vec<AccountId>>;
+
+		/// Every member reachable from an originator with their depth in the graph. Originators are at depth 0
+		fn member_depths() -> Vec<(AccountId, u32)>;
+	}
+}
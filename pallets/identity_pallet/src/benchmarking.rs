@@ -0,0 +1,99 @@
+//! Benchmarking setup for identity-pallet
+
+use super::*;
+
+use crate::Pallet as IdentityPallet;
+use crate::pallet::PruneCursor;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{traits::{Currency, EnsureOrigin, Hooks}, weights::Weight};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+// Upper bound for the number of expired members pruned in one block in the benchmarks below
+const MAX_EXPIRED: u32 = 100;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+// Funds an account and has root add it to the voter set. Works whatever members the chain spec seeds, unlike joining
+// as an originator
+fn member<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::from(u32::MAX));
+	IdentityPallet::<T>::force_add_member(RawOrigin::Root.into(), who.clone()).unwrap();
+	who
+}
+
+// Gives `vouchee` one vouch short of MinVouches, from fresh members
+fn pending_vouchee<T: Config>(vouchee: &T::AccountId) {
+	for i in 0..T::MinVouches::get().saturating_sub(1) {
+		let voucher = member::<T>("voucher", i);
+		IdentityPallet::<T>::vouch_for(RawOrigin::Signed(voucher).into(), vouchee.clone()).unwrap();
+	}
+}
+
+benchmarks! {
+
+	// The vouch that admits its vouchee, moving every pending voucher over to Vouchers
+	vouch_for {
+		let caller = member::<T>("caller", 0);
+		let vouchee: T::AccountId = account("vouchee", 0, SEED);
+		pending_vouchee::<T>(&vouchee);
+	}: _(RawOrigin::Signed(caller.clone()), vouchee.clone())
+	verify {
+		assert_last_event::<T>(Event::VoterVouchedForNonVoter(caller, vouchee).into());
+	}
+
+	revoke_vouch {
+		let caller = member::<T>("caller", 0);
+		let vouchee: T::AccountId = account("vouchee", 0, SEED);
+		IdentityPallet::<T>::vouch_for(RawOrigin::Signed(caller.clone()).into(), vouchee.clone())?;
+	}: _(RawOrigin::Signed(caller.clone()), vouchee.clone())
+	verify {
+		assert_last_event::<T>(Event::VouchRevoked(caller, vouchee).into());
+	}
+
+	// Adding an account that already has vouches pending, which become its vouchers
+	force_add_member {
+		let who: T::AccountId = account("who", 0, SEED);
+		pending_vouchee::<T>(&who);
+	}: _(RawOrigin::Root, who.clone())
+	verify {
+		assert_last_event::<T>(Event::MemberForceAdded(who).into());
+	}
+
+	// Removing a member with `e` pending vouches, each of which is dropped and has its bond returned
+	remove_member {
+		let e in 0 .. T::MaxVouchees::get();
+		let who = member::<T>("who", 0);
+		for i in 0..e {
+			let vouchee: T::AccountId = account("vouchee", i, SEED);
+			IdentityPallet::<T>::vouch_for(RawOrigin::Signed(who.clone()).into(), vouchee)?;
+		}
+		let origin = T::RemoveOrigin::successful_origin();
+	}: _<T::Origin>(origin, who.clone(), false, 0, e)
+	verify {
+		assert_eq!(IdentityPallet::<T>::get_voter_from_set(who), None);
+	}
+
+	// Pruning `m` members whose memberships ran out in the same block
+	on_idle {
+		let m in 0 .. MAX_EXPIRED;
+		for i in 0..m {
+			member::<T>("expiring", i);
+		}
+		let now = frame_system::Pallet::<T>::block_number() + T::MembershipPeriod::get().into();
+		frame_system::Pallet::<T>::set_block_number(now);
+		IdentityPallet::<T>::on_initialize(now);
+		// Only this block's expiries are walked, not every block since genesis
+		PruneCursor::<T>::put(now);
+	}: {
+		IdentityPallet::<T>::on_idle(now, Weight::MAX);
+	}
+	verify {
+		assert_eq!(IdentityPallet::<T>::get_expired_count(), 0);
+	}
+
+	impl_benchmark_test_suite!(IdentityPallet, crate::mock::ExtBuilder::build(), crate::mock::Test);
+}
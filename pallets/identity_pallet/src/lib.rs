@@ -6,6 +6,8 @@ use frame_support::pallet_prelude::DispatchResult;
 /// <https://docs.substrate.io/v3/runtime/frame>
 pub use pallet::*;
 
+pub mod migrations;
+
 #[cfg(test)]
 mod mock;
 
@@ -18,7 +20,37 @@ mod benchmarking;
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::{pallet_prelude::*, Blake2_128, BoundedVec};
+	use frame_support::storage::{with_transaction, TransactionOutcome};
+	use frame_support::traits::{Currency, Imbalance, OnUnbalanced, ReservableCurrency};
+	use frame_support::sp_runtime::{traits::{One, Saturating, Zero}, Perbill};
 	use frame_system::pallet_prelude::*;
+	use frame_support::sp_std::{collections::{btree_map::BTreeMap, vec_deque::VecDeque}, vec::Vec};
+	use brads_soft_coupling::OnMemberRemoved;
+
+	/// The current storage version
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+	pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+
+	// What is recorded about a member when they join the voter set
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct MemberRecord<BlockNumber> {
+		/// Block the member was admitted to the voter set
+		pub joined_at: BlockNumber,
+		/// Whether the member roots the social graph rather than having been vouched in: originators, genesis members
+		/// and accounts force added without any pending vouch
+		pub originator: bool,
+	}
+
+	// A bond reserved from a voucher for one vouch
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct VouchBondInfo<Balance, BlockNumber> {
+		/// Amount reserved from the voucher
+		pub amount: Balance,
+		/// Block from which the voucher can release the bond, and after which it can no longer be slashed
+		pub matures_at: BlockNumber,
+	}
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
@@ -28,11 +60,41 @@ pub mod pallet {
 		/// The pallet needs a minimum threshold to add a member to the social graph. Including it in the Config makes this threshold configurable
 		#[pallet::constant]
 		type MinVouches: Get<u32>;
+		/// Origin allowed to remove members from the social graph, e.g. once they are shown to be sybils
+		type RemoveOrigin: EnsureOrigin<Self::Origin>;
+		/// Blocks a membership lasts before it has to be renewed. Expired members stop counting as voters straight away
+		/// and are pruned from the voter set in `on_idle`
+		#[pallet::constant]
+		type MembershipPeriod: Get<u32>;
+		/// Fresh vouches from other members needed to renew a membership. With 0, members renew themselves by calling
+		/// `reattest`
+		#[pallet::constant]
+		type ReattestationVouches: Get<u32>;
+		/// Currency the vouch bonds are reserved in
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Amount reserved from a member each time they vouch for a non voter. Zero turns vouch bonds off
+		#[pallet::constant]
+		type VouchBond: Get<BalanceOf<Self>>;
+		/// Blocks after a vouch until its bond can be released
+		#[pallet::constant]
+		type BondMaturity: Get<u32>;
+		/// Share of an unmatured bond slashed when its vouchee is removed by RemoveOrigin. The rest is returned
+		#[pallet::constant]
+		type BondSlash: Get<Perbill>;
+		/// Handler for slashed vouch bonds
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// Told about every account that leaves the voter set, so other pallets can drop what they keep on it
+		type OnMemberRemoved: OnMemberRemoved<Self::AccountId>;
+		/// Most accounts one member can have pending vouches for at once. A vouch stops counting once its vouchee joins
+		/// the voter set
+		#[pallet::constant]
+		type MaxVouchees: Get<u32>;
 	}
 
 	// The struct on which we build all of our pallet logic
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	//Pallet storage items
@@ -46,13 +108,78 @@ pub mod pallet {
 	/// the social graph as full members.
 	pub(super) type VouchedForSet<T: Config> = StorageMap<_, Blake2_128, T::AccountId, VouchersFor<T>>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn get_member_record)]
+	/// Admission block and originator status of every member of the voter set
+	pub(super) type MemberRecords<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, MemberRecord<T::BlockNumber>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vouchers)]
+	/// The members whose vouches brought a member into the voter set, minus any that have since been removed. Originators have none.
+	pub(super) type Vouchers<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, VouchersFor<T>, ValueQuery>;
+
+	#[pallet::storage]
+	/// Every account a voucher has vouched for, pending or graduated, keyed by voucher then vouchee. Lets removals find the
+	/// accounts that leaned on the removed member.
+	pub(super) type Vouchees<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vouchee_count)]
+	/// Number of accounts each voucher has a pending vouch for, i.e. that are still in `VouchedForSet`. Kept within
+	/// MaxVouchees
+	pub(super) type VoucheeCount<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_edge_count)]
+	/// Number of `Vouchees` entries under each voucher, pending or admitted. What removing the voucher has to walk
+	pub(super) type EdgeCount<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vouch_bond)]
+	/// Bonds held for each (voucher, vouchee) edge, keyed by vouchee then voucher so a removal finds every bond on the
+	/// removed member
+	pub(super) type VouchBonds<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, VouchBondInfo<BalanceOf<T>, T::BlockNumber>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_membership_expiry)]
+	/// The block at which each member's membership runs out unless it is renewed
+	pub(super) type MembershipExpiry<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	#[pallet::storage]
+	/// Members keyed by the block their membership runs out, so `on_idle` can find the expired ones
+	pub(super) type ExpiryQueue<T: Config> = StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	#[pallet::storage]
+	/// The next block of `ExpiryQueue` that `on_idle` will prune
+	pub(super) type PruneCursor<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	#[pallet::storage]
+	/// Number of memberships running out at each block that hasn't been reached yet. Moved into `ExpiredCount` in
+	/// `on_initialize` of that block
+	pub(super) type ExpiryCounts<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_expired_count)]
+	/// Number of members whose membership has run out but who haven't been pruned from the voter set yet
+	pub(super) type ExpiredCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_bootstrap_complete)]
+	/// Set once the voter set first reaches MinVouches members. From then on nobody can join as an originator, even
+	/// if removals shrink the voter set again
+	pub(super) type BootstrapComplete<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_reattestations)]
+	/// Members who have vouched for a member's renewal during the current membership period. Cleared once there are
+	/// ReattestationVouches of them.
+	pub(super) type Reattestations<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<T::AccountId, T::ReattestationVouches>, ValueQuery>;
+
 	// Non storage items
 	// The set of accountIds which have vouched for a particular accountId. Capped at MinVouches, since upon reaching MinVouches the vouched for
 	// account id is added to the voter set and the VouchersFor structure is disposed of.
 	pub(super) type VouchersFor<T> = BoundedVec<<T as frame_system::Config>::AccountId, <T as Config>::MinVouches>;
 
-	// Should introduce new structure to keep track of who has already voted on the current proposal
-	
 	// Pallets events
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -61,6 +188,26 @@ pub mod pallet {
 		OriginatorAdded(T::AccountId),
 		/// A voter has vouched for a non voter [voter, nonVoter]
 		VoterVouchedForNonVoter(T::AccountId, T::AccountId),
+		/// A voter has taken back their vouch for a non voter that had not yet joined the voter set [voter, nonVoter]
+		VouchRevoked(T::AccountId, T::AccountId),
+		/// An account was added to the voter set by root [member]
+		MemberForceAdded(T::AccountId),
+		/// A member was removed from the voter set by RemoveOrigin [member]
+		MemberRemoved(T::AccountId),
+		/// A member was removed because every member that vouched for them has been removed [member, lastVoucher]
+		MemberRemovedByCascade(T::AccountId, T::AccountId),
+		/// A member vouched for another member's renewal [voter, member]
+		ReattestationVouched(T::AccountId, T::AccountId),
+		/// A membership was renewed [member, expiresAt]
+		MembershipRenewed(T::AccountId, T::BlockNumber),
+		/// A member was pruned from the voter set after their membership ran out [member]
+		MembershipExpired(T::AccountId),
+		/// A bond was reserved for a vouch [voter, nonVoter, amount]
+		VouchBonded(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// A vouch bond was returned to its voucher [voter, vouchee, amount]
+		VouchBondReleased(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// Part of a vouch bond was slashed because the vouchee was removed before it matured [voter, vouchee, slashed]
+		VouchBondSlashed(T::AccountId, T::AccountId, BalanceOf<T>),
 	}
 
 	// Pallet Errors
@@ -68,19 +215,103 @@ pub mod pallet {
 	pub enum Error<T> {
 		/// Proposed originator is already in the voter set
 		AlreadyInSet,
-		/// Tried to add social graph originators when there are enough
+		/// Tried to add social graph originators once the voter set has reached MinVouches members
 		NoNeedForAdditionalOriginators,
 		/// Voucher isn't in the voter set
 		VoucherNotInVoterSet,
 		/// Voter can only vouch for each other once
 		VouchedForSameTwice,
+		/// There is no pending vouch from this voter for that account
+		NotVouchedFor,
+		/// Account isn't in the voter set
+		NotInVoterSet,
+		/// The cascade would remove more members than the `max_cascade` given for it
+		CascadeTooLarge,
+		/// The removal would walk more vouch edges than the `max_edges` given for it
+		TooManyEdges,
+		/// Memberships are renewed by vouches from other members, not by `reattest`
+		ReattestationNeedsVouches,
+		/// Voucher can't reserve the VouchBond
+		InsufficientVouchBond,
+		/// There is no bond from this voucher on that account
+		NoVouchBond,
+		/// The bond's maturity period hasn't passed yet
+		VouchBondNotMatured,
+		/// Voucher has MaxVouchees pending vouches already
+		TooManyVouchees,
+		/// The account's membership ran out and it is waiting to be pruned from the voter set
+		ExpiredMemberNotPruned,
+	}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Accounts in the voter set from the first block, with a full MembershipPeriod each
+		pub members: Vec<T::AccountId>,
+		/// Pending vouches from those members for accounts that haven't joined yet [voucher, vouchee]. No bond is reserved
+		/// for them, and no account may collect MinVouches of them.
+		pub vouches: Vec<(T::AccountId, T::AccountId)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { members: Vec::new(), vouches: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for member in &self.members {
+				assert!(!VoterSet::<T>::contains_key(member), "Genesis members must be unique");
+				Pallet::<T>::admit(member, true);
+			}
+			for (voucher, vouchee) in &self.vouches {
+				assert!(VoterSet::<T>::contains_key(voucher), "Genesis vouches must come from genesis members");
+				assert!(!VoterSet::<T>::contains_key(vouchee), "Genesis vouches can't be for genesis members");
+				let vouchers = VouchedForSet::<T>::get(vouchee).unwrap_or_default();
+				assert!(!vouchers.contains(voucher), "Genesis vouches must be unique");
+				assert!(
+					vouchers.len() + 1 < T::MinVouches::get() as usize,
+					"Genesis vouchee would reach MinVouches, list them as a member instead"
+				);
+				VouchedForSet::<T>::try_append(vouchee, voucher.clone()).expect("Already checked that there is room");
+				assert!(VoucheeCount::<T>::get(voucher) < T::MaxVouchees::get(), "Genesis voucher would exceed MaxVouchees");
+				Pallet::<T>::add_vouchee(voucher, vouchee);
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// Memberships running out this block stop counting as voters
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let expiring = ExpiryCounts::<T>::take(now);
+			if expiring > 0 {
+				ExpiredCount::<T>::mutate(|count| *count = count.saturating_add(expiring));
+			}
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::prune_expired(now, remaining_weight)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+				.saturating_add(crate::migrations::v2::migrate::<T>())
+				.saturating_add(crate::migrations::v3::migrate::<T>())
+		}
 	}
 
 	// Callable extrinsic functions for this pallet
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Any social graph needs to start somewhere. Add this caller to the set if set size below minVouches.
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		/// Any social graph needs to start somewhere. Add this caller to the set if set size below minVouches. Closes for
+		/// good once the set first reaches minVouches members.
+		/// Events: OriginatorAdded
+		/// Errors: AlreadyInSet, NoNeedForAdditionalOriginators
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 8))]
 		pub fn try_add_as_social_graph_originator(origin: OriginFor<T>) -> DispatchResult {
 			let sender = ensure_signed(origin.clone())?;
 			Self::add_originator_impl(sender)?;
@@ -89,40 +320,133 @@ pub mod pallet {
 
 		/// Vouch for another public key. If not enough to add them to voter set, then creates or 
 		/// increments entry in vouched for set.
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+		/// Events: VoterVouchedForNonVoter, VouchBonded, OriginatorAdded, ReattestationVouched, MembershipRenewed
+		/// Errors: VoucherNotInVoterSet, ExpiredMemberNotPruned, VouchedForSameTwice, TooManyVouchees, InsufficientVouchBond
+		// The voucher's membership and vouchee count, the vouchee's membership and pending vouches, and the bond, then
+		// admitting the vouchee on their last vouch
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(9, 15))]
 		pub fn vouch_for(origin: OriginFor<T>, other: T::AccountId) -> DispatchResult {
 			let sender = ensure_signed(origin.clone())?;
 			Self::vouch_for_impl(sender, other)?;
 			Ok(())
 		}
+
+		/// Take back a vouch for an account which hasn't collected enough vouches to join the voter set yet.
+		/// Events: VouchRevoked
+		/// Errors: NotVouchedFor
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 5))]
+		pub fn revoke_vouch(origin: OriginFor<T>, other: T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::revoke_vouch_impl(sender, other)?;
+			Ok(())
+		}
+
+		/// Add an account straight to the voter set, e.g. to bootstrap the social graph after launch. Vouches already
+		/// pending for the account are kept as its vouchers. Must be called by root.
+		/// Events: MemberForceAdded
+		/// Errors: AlreadyInSet
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3,5))]
+		pub fn force_add_member(origin: OriginFor<T>, member: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::force_add_member_impl(member)?;
+			Ok(())
+		}
+
+		/// Remove a member from the voter set, along with their pending vouches. With `cascade`, members whose every voucher
+		/// has now been removed go too, and so on down the vouch graph. Unmatured bonds on the removed members are slashed
+		/// by BondSlash. `max_cascade` is the most members the cascade may remove besides `member`, and `max_edges` the most
+		/// vouch edges of removed members it may walk, as counted by `EdgeCount`. Both are what the weight is charged for
+		/// up front. If the removal would go further, nothing is removed. Must be called by RemoveOrigin.
+		/// Events: MemberRemoved, MemberRemovedByCascade, VouchBondSlashed, VouchBondReleased
+		/// Errors: NotInVoterSet, CascadeTooLarge, TooManyEdges
+		#[pallet::weight(10_000 + Pallet::<T>::removal_weight(1 + if *cascade { *max_cascade } else { 0 }, *max_edges))]
+		pub fn remove_member(
+			origin: OriginFor<T>,
+			member: T::AccountId,
+			cascade: bool,
+			max_cascade: u32,
+			max_edges: u32,
+		) -> DispatchResultWithPostInfo {
+			T::RemoveOrigin::ensure_origin(origin)?;
+			let (removed, edges) = Self::remove_member_impl(member, cascade, max_cascade, max_edges)?;
+			Ok(Some(10_000 + Self::removal_weight(removed, edges)).into())
+		}
+
+		/// Return your bond for a vouch once BondMaturity blocks have passed since the vouch.
+		/// Events: VouchBondReleased
+		/// Errors: NoVouchBond, VouchBondNotMatured
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2,2))]
+		pub fn release_vouch_bond(origin: OriginFor<T>, vouchee: T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::release_vouch_bond_impl(sender, vouchee)?;
+			Ok(())
+		}
+
+		/// Renew your membership for another MembershipPeriod. Only available when ReattestationVouches is 0.
+		/// Events: MembershipRenewed
+		/// Errors: NotInVoterSet, ReattestationNeedsVouches
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3,4))]
+		pub fn reattest(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::reattest_impl(sender)?;
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
 		fn add_originator_impl(sender: T::AccountId) -> Result<(), DispatchError> {
 			// Check failure conditions, that the accountId to be added is already in the voter set or the voter set has >= MinVouches members
 			ensure!(!VoterSet::<T>::contains_key(sender.clone()), Error::<T>::AlreadyInSet);
+			ensure!(!BootstrapComplete::<T>::get(), Error::<T>::NoNeedForAdditionalOriginators);
 			ensure!(VoterSet::<T>::count() < T::MinVouches::get(), Error::<T>::NoNeedForAdditionalOriginators);
 			// Add to voter set
-			VoterSet::<T>::insert(sender.clone(), ());
+			Self::admit(&sender, true);
 			// Send success event
 			Self::deposit_event(Event::OriginatorAdded(sender));
 			Ok(())
 		}
 
+		fn force_add_member_impl(member: T::AccountId) -> Result<(), DispatchError> {
+			ensure!(!VoterSet::<T>::contains_key(member.clone()), Error::<T>::AlreadyInSet);
+			let vouchers = VouchedForSet::<T>::take(member.clone()).unwrap_or_default();
+			Self::admit(&member, vouchers.is_empty());
+			if !vouchers.is_empty() {
+				Self::uncount_pending_vouches(&vouchers);
+				Vouchers::<T>::insert(member.clone(), vouchers);
+			}
+			// Send success event
+			Self::deposit_event(Event::MemberForceAdded(member));
+			Ok(())
+		}
+
 		fn vouch_for_impl(sender: T::AccountId, other: T::AccountId) -> Result<(), DispatchError> {
 			// Check that voucher is in the social graph, and therefore has rights to vouch
-			ensure!(VoterSet::<T>::contains_key(sender.clone()), Error::<T>::VoucherNotInVoterSet);
-			// It doesn't seem like an error to vouch for someone already in the voter set, but it is grounds for an early return
-			if VoterSet::<T>::contains_key(other.clone()) { return Ok(()); }
+			ensure!(Self::is_member(&sender), Error::<T>::VoucherNotInVoterSet);
+			// A member whose membership ran out can be vouched back in like anyone else once on_idle has pruned them
+			if VoterSet::<T>::contains_key(other.clone()) {
+				ensure!(Self::is_member(&other), Error::<T>::ExpiredMemberNotPruned);
+				// Vouching for someone already in the voter set counts towards their renewal, if renewals take vouches
+				return Self::vouch_for_renewal(sender, other);
+			}
+			let pending = VouchedForSet::<T>::get(other.clone());
+			// One account id can't vouch for another more than once. This would harm social graph security
+			ensure!(!pending.as_ref().map_or(false, |vouchers| vouchers.contains(&sender)), Error::<T>::VouchedForSameTwice);
+			ensure!(VoucheeCount::<T>::get(&sender) < T::MaxVouchees::get(), Error::<T>::TooManyVouchees);
+			// Reserve the bond before anything is written, since the voucher may not be able to afford it
+			Self::bond_vouch(&sender, &other)?;
+			Self::add_vouchee(&sender, &other);
 			// If other is in the VouchedForSet, then we want to try to run logic to add a vouch to their VouchersFor vec, or graduate them 
 			// to the voter set
-			if let Some(vouchers) = VouchedForSet::<T>::get(other.clone()) {
-				// One account id can't vouch for another more than once. This would harm social graph security
-				ensure!(!vouchers.contains(&sender), Error::<T>::VouchedForSameTwice);
+			if let Some(vouchers) = pending {
 				// If adding a voucher would graduate the address `other` to a full social graph member, then add other to the voter set and remove
 				// from VouchedForSet
 				if vouchers.len() + 1 >= T::MinVouches::get() as usize {
-					VoterSet::<T>::insert(other.clone(), ());
+					let mut vouchers = vouchers;
+					vouchers.try_push(sender.clone()).expect("Vouchers never reach MinVouches while pending");
+					Self::admit(&other, false);
+					// Vouches that brought `other` in stop counting against their vouchers' MaxVouchees
+					Self::uncount_pending_vouches(&vouchers);
+					Vouchers::<T>::insert(other.clone(), vouchers);
 					VouchedForSet::<T>::remove(other.clone());
 				}
 				// Else add to VouchersFor `other`
@@ -139,6 +463,375 @@ pub mod pallet {
 			Self::deposit_event(Event::VoterVouchedForNonVoter(sender, other));
 			Ok(())
 		}
+
+		fn revoke_vouch_impl(sender: T::AccountId, other: T::AccountId) -> Result<(), DispatchError> {
+			let mut vouchers = VouchedForSet::<T>::get(other.clone()).ok_or(Error::<T>::NotVouchedFor)?;
+			ensure!(vouchers.contains(&sender), Error::<T>::NotVouchedFor);
+			vouchers.retain(|voucher| *voucher != sender);
+			if vouchers.is_empty() {
+				VouchedForSet::<T>::remove(other.clone());
+			} else {
+				VouchedForSet::<T>::insert(other.clone(), vouchers);
+			}
+			Self::remove_vouchee(&sender, &other, true);
+			Self::return_vouch_bond(&sender, &other);
+			// Send success event
+			Self::deposit_event(Event::VouchRevoked(sender, other));
+			Ok(())
+		}
+
+		// Returns the number of members removed, including `member`, and of vouch edges walked
+		fn remove_member_impl(member: T::AccountId, cascade: bool, max_cascade: u32, max_edges: u32) -> Result<(u32, u32), DispatchError> {
+			ensure!(VoterSet::<T>::contains_key(member.clone()), Error::<T>::NotInVoterSet);
+			// A removal that goes past max_cascade or max_edges is only found out partway, so undo what it did by then
+			with_transaction(|| {
+				Self::deposit_event(Event::MemberRemoved(member.clone()));
+				match Self::remove_from_graph(member, cascade, true, max_cascade, max_edges) {
+					Ok(removed) => TransactionOutcome::Commit(Ok(removed)),
+					Err(error) => TransactionOutcome::Rollback(Err(error.into())),
+				}
+			})
+		}
+
+		fn release_vouch_bond_impl(sender: T::AccountId, vouchee: T::AccountId) -> Result<(), DispatchError> {
+			let bond = VouchBonds::<T>::get(vouchee.clone(), sender.clone()).ok_or(Error::<T>::NoVouchBond)?;
+			ensure!(frame_system::Pallet::<T>::block_number() >= bond.matures_at, Error::<T>::VouchBondNotMatured);
+			Self::return_vouch_bond(&sender, &vouchee);
+			Ok(())
+		}
+
+		// Reserves VouchBond from a voucher against the account they vouched for, unless bonds are turned off
+		fn bond_vouch(voucher: &T::AccountId, vouchee: &T::AccountId) -> Result<(), DispatchError> {
+			let amount = T::VouchBond::get();
+			if amount.is_zero() { return Ok(()); }
+			T::Currency::reserve(voucher, amount).map_err(|_| Error::<T>::InsufficientVouchBond)?;
+			let matures_at = frame_system::Pallet::<T>::block_number().saturating_add(T::BondMaturity::get().into());
+			VouchBonds::<T>::insert(vouchee, voucher, VouchBondInfo { amount, matures_at });
+			Self::deposit_event(Event::VouchBonded(voucher.clone(), vouchee.clone(), amount));
+			Ok(())
+		}
+
+		// Unreserves the bond for a vouch in full, if there is one
+		fn return_vouch_bond(voucher: &T::AccountId, vouchee: &T::AccountId) {
+			if let Some(bond) = VouchBonds::<T>::take(vouchee, voucher) {
+				T::Currency::unreserve(voucher, bond.amount);
+				Self::deposit_event(Event::VouchBondReleased(voucher.clone(), vouchee.clone(), bond.amount));
+			}
+		}
+
+		// Settles every bond on a member removed by RemoveOrigin. Unmatured bonds lose BondSlash to the Slash handler
+		fn slash_vouch_bonds(vouchee: &T::AccountId) {
+			let now = frame_system::Pallet::<T>::block_number();
+			for (voucher, bond) in VouchBonds::<T>::drain_prefix(vouchee) {
+				if now >= bond.matures_at {
+					T::Currency::unreserve(&voucher, bond.amount);
+					Self::deposit_event(Event::VouchBondReleased(voucher, vouchee.clone(), bond.amount));
+					continue;
+				}
+				let (imbalance, _) = T::Currency::slash_reserved(&voucher, T::BondSlash::get() * bond.amount);
+				let slashed = imbalance.peek();
+				T::Slash::on_unbalanced(imbalance);
+				T::Currency::unreserve(&voucher, bond.amount.saturating_sub(slashed));
+				Self::deposit_event(Event::VouchBondSlashed(voucher, vouchee.clone(), slashed));
+			}
+		}
+
+		fn reattest_impl(sender: T::AccountId) -> Result<(), DispatchError> {
+			ensure!(Self::is_member(&sender), Error::<T>::NotInVoterSet);
+			ensure!(T::ReattestationVouches::get() == 0, Error::<T>::ReattestationNeedsVouches);
+			let expires_at = Self::renew_membership(&sender);
+			// Send success event
+			Self::deposit_event(Event::MembershipRenewed(sender, expires_at));
+			Ok(())
+		}
+
+		fn vouch_for_renewal(sender: T::AccountId, other: T::AccountId) -> Result<(), DispatchError> {
+			// Without renewal vouches, or for oneself, there is nothing to record
+			if T::ReattestationVouches::get() == 0 || sender == other { return Ok(()); }
+			let mut vouchers = Reattestations::<T>::get(other.clone());
+			ensure!(!vouchers.contains(&sender), Error::<T>::VouchedForSameTwice);
+			if vouchers.len() + 1 >= T::ReattestationVouches::get() as usize {
+				let expires_at = Self::renew_membership(&other);
+				Self::deposit_event(Event::MembershipRenewed(other, expires_at));
+			} else {
+				vouchers.try_push(sender.clone()).expect("Already checked that there is room");
+				Reattestations::<T>::insert(other.clone(), vouchers);
+				Self::deposit_event(Event::ReattestationVouched(sender, other));
+			}
+			Ok(())
+		}
+
+		// Puts an account in the voter set with a fresh membership
+		fn admit(who: &T::AccountId, originator: bool) {
+			VoterSet::<T>::insert(who, ());
+			MemberRecords::<T>::insert(who, MemberRecord { joined_at: frame_system::Pallet::<T>::block_number(), originator });
+			Self::renew_membership(who);
+			if VoterSet::<T>::count() >= T::MinVouches::get() && !BootstrapComplete::<T>::get() {
+				BootstrapComplete::<T>::put(true);
+			}
+		}
+
+		// Records a new pending vouch
+		fn add_vouchee(voucher: &T::AccountId, vouchee: &T::AccountId) {
+			Vouchees::<T>::insert(voucher, vouchee, ());
+			VoucheeCount::<T>::mutate(voucher, |count| *count = count.saturating_add(1));
+			EdgeCount::<T>::mutate(voucher, |count| *count = count.saturating_add(1));
+		}
+
+		// Drops a vouch edge. `pending` if the vouchee hasn't joined the voter set, so the vouch still counts against
+		// MaxVouchees
+		fn remove_vouchee(voucher: &T::AccountId, vouchee: &T::AccountId, pending: bool) {
+			if Vouchees::<T>::take(voucher, vouchee).is_some() {
+				if pending {
+					VoucheeCount::<T>::mutate_exists(voucher, |count| {
+						*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+					});
+				}
+				EdgeCount::<T>::mutate_exists(voucher, |count| {
+					*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+				});
+			}
+		}
+
+		// The vouches of a newly admitted member's vouchers are no longer pending. Their edges stay
+		fn uncount_pending_vouches(vouchers: &[T::AccountId]) {
+			for voucher in vouchers {
+				VoucheeCount::<T>::mutate_exists(voucher, |count| {
+					*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+				});
+			}
+		}
+
+		/// Members whose membership hasn't run out, whether or not on_idle has pruned the rest yet
+		pub fn live_member_count() -> u32 {
+			VoterSet::<T>::count().saturating_sub(ExpiredCount::<T>::get())
+		}
+
+		/// Most weight taking `members` members with `edges` vouch edges between them out of the voter set can use. Each
+		/// member costs their own entries, an edge from each of their at most MinVouches vouchers with its bond, and
+		/// whatever OnMemberRemoved does with them. Each edge costs its vouchee's pending vouch or vouchers, and its bond
+		pub fn removal_weight(members: u32, edges: u32) -> Weight {
+			let vouchers = T::MinVouches::get() as Weight;
+			T::DbWeight::get().reads_writes(6, 10)
+				.saturating_add(T::DbWeight::get().reads_writes(3, 4).saturating_mul(vouchers))
+				.saturating_add(T::OnMemberRemoved::max_weight())
+				.saturating_mul(members as Weight)
+				.saturating_add(T::DbWeight::get().reads_writes(6, 5).saturating_mul(edges as Weight))
+		}
+
+		/// Members whose vouches admitted `who`, minus any that have since been removed
+		pub fn vouchers_of(who: &T::AccountId) -> Vec<T::AccountId> {
+			Vouchers::<T>::get(who).into_inner()
+		}
+
+		/// Accounts `who` has vouched for, whether they have joined the voter set yet or not
+		pub fn vouchees_of(who: &T::AccountId) -> Vec<T::AccountId> {
+			Vouchees::<T>::iter_key_prefix(who).collect()
+		}
+
+		/// Shortest chain of vouchers from `who` back to an originator, starting with `who` and ending with the
+		/// originator. `None` if `who` isn't a member or every chain passes through a removed member
+		pub fn path_to_originator(who: &T::AccountId) -> Option<Vec<T::AccountId>> {
+			// Breadth first up the voucher edges, remembering which vouchee each member was reached from
+			let mut reached_from: BTreeMap<T::AccountId, Option<T::AccountId>> = BTreeMap::new();
+			let mut queue = VecDeque::new();
+			reached_from.insert(who.clone(), None);
+			queue.push_back(who.clone());
+			while let Some(member) = queue.pop_front() {
+				let record = match MemberRecords::<T>::get(&member) {
+					Some(record) => record,
+					None => continue,
+				};
+				if record.originator {
+					let mut path = frame_support::sp_std::vec![member.clone()];
+					let mut step = member;
+					while let Some(Some(vouchee)) = reached_from.get(&step) {
+						path.push(vouchee.clone());
+						step = vouchee.clone();
+					}
+					path.reverse();
+					return Some(path)
+				}
+				for voucher in Vouchers::<T>::get(&member) {
+					if !reached_from.contains_key(&voucher) {
+						reached_from.insert(voucher.clone(), Some(member.clone()));
+						queue.push_back(voucher);
+					}
+				}
+			}
+			None
+		}
+
+		/// Every member reachable from an originator, with the fewest vouch edges between them and any originator.
+		/// Originators are at depth 0
+		pub fn member_depths() -> Vec<(T::AccountId, u32)> {
+			let mut depths: BTreeMap<T::AccountId, u32> = BTreeMap::new();
+			let mut queue = VecDeque::new();
+			for (member, record) in MemberRecords::<T>::iter() {
+				if record.originator {
+					depths.insert(member.clone(), 0);
+					queue.push_back(member);
+				}
+			}
+			// Breadth first down the vouch edges, so each member is first reached along a shortest path
+			while let Some(voucher) = queue.pop_front() {
+				let depth = depths.get(&voucher).copied().unwrap_or_default().saturating_add(1);
+				for vouchee in Vouchees::<T>::iter_key_prefix(&voucher) {
+					if !depths.contains_key(&vouchee) && MemberRecords::<T>::contains_key(&vouchee) {
+						depths.insert(vouchee.clone(), depth);
+						queue.push_back(vouchee);
+					}
+				}
+			}
+			depths.into_iter().collect()
+		}
+
+		/// Whether an account is in the voter set with a membership that hasn't run out
+		pub fn is_member(who: &T::AccountId) -> bool {
+			let now = frame_system::Pallet::<T>::block_number();
+			VoterSet::<T>::contains_key(who) && MembershipExpiry::<T>::get(who).map_or(true, |expires_at| expires_at > now)
+		}
+
+		// Starts or renews a membership for another MembershipPeriod, returning when it will run out
+		fn renew_membership(who: &T::AccountId) -> T::BlockNumber {
+			if let Some(expires_at) = MembershipExpiry::<T>::get(who) {
+				ExpiryQueue::<T>::remove(expires_at, who);
+				Self::uncount_expiry(expires_at);
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			let expires_at = now.saturating_add(T::MembershipPeriod::get().into());
+			MembershipExpiry::<T>::insert(who, expires_at);
+			ExpiryQueue::<T>::insert(expires_at, who, ());
+			// on_initialize has already run for this block, so a membership running out now counts as expired at once
+			if expires_at > now {
+				ExpiryCounts::<T>::mutate(expires_at, |count| *count = count.saturating_add(1));
+			} else {
+				ExpiredCount::<T>::mutate(|count| *count = count.saturating_add(1));
+			}
+			Reattestations::<T>::remove(who);
+			expires_at
+		}
+
+		// Takes a membership out of whichever expiry count it is in
+		fn uncount_expiry(expires_at: T::BlockNumber) {
+			if expires_at > frame_system::Pallet::<T>::block_number() {
+				ExpiryCounts::<T>::mutate_exists(expires_at, |count| {
+					*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+				});
+			} else {
+				ExpiredCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			}
+		}
+
+		// Prunes a member whose membership has run out
+		fn expire_member(who: T::AccountId) {
+			Self::deposit_event(Event::MembershipExpired(who.clone()));
+			// Without a cascade nothing past `who` is removed, and prune_expired has already costed their edges
+			let _ = Self::remove_from_graph(who, false, false, 0, u32::MAX);
+		}
+
+		// Walks ExpiryQueue from PruneCursor up to `now`, pruning expired members until the weight limit is reached.
+		// Returns the weight used
+		fn prune_expired(now: T::BlockNumber, limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			// Reading the queue for one block and moving the cursor past it
+			let per_block = db_weight.reads(1);
+			// Reading a member's edge count, plus everything taking them out of the graph touches when they have no edges
+			let per_member = db_weight.reads(1).saturating_add(Self::removal_weight(1, 0)).max(1);
+			let mut used = db_weight.reads_writes(1, 1);
+			if used > limit {
+				return 0
+			}
+			let mut cursor = PruneCursor::<T>::get();
+			while cursor <= now && used.saturating_add(per_block).saturating_add(per_member) <= limit {
+				used = used.saturating_add(per_block);
+				let affordable = ((limit - used) / per_member) as usize;
+				let expired: Vec<T::AccountId> = ExpiryQueue::<T>::iter_key_prefix(cursor).take(affordable + 1).collect();
+				let mut finished = expired.len() <= affordable;
+				for who in expired.into_iter().take(affordable) {
+					// Members with many vouch edges cost more, and may have to wait for a block with more room
+					let cost = db_weight.reads(1).saturating_add(Self::removal_weight(1, EdgeCount::<T>::get(&who)));
+					if used.saturating_add(cost) > limit {
+						used = used.saturating_add(db_weight.reads(1));
+						finished = false;
+						break
+					}
+					Self::expire_member(who);
+					used = used.saturating_add(cost);
+				}
+				if !finished {
+					break
+				}
+				cursor = cursor.saturating_add(One::one());
+			}
+			PruneCursor::<T>::put(cursor);
+			used
+		}
+
+		// Takes a member out of the voter set along with their pending vouches, whose bonds are returned. With `cascade`,
+		// members left without any voucher are taken out too. With `slash`, bonds on the removed members are slashed.
+		// Returns the number of members removed and of their vouch edges walked, or an error, partway through, once the
+		// cascade goes past `max_cascade` or the edges past `max_edges`
+		fn remove_from_graph(
+			member: T::AccountId,
+			cascade: bool,
+			slash: bool,
+			max_cascade: u32,
+			max_edges: u32,
+		) -> Result<(u32, u32), Error<T>> {
+			// Members still to be taken out of the graph
+			let mut to_remove = frame_support::sp_std::vec![member];
+			let mut removed_count: u32 = 0;
+			let mut edge_count: u32 = 0;
+			while let Some(removed) = to_remove.pop() {
+				removed_count += 1;
+				ensure!(removed_count <= max_cascade.saturating_add(1), Error::<T>::CascadeTooLarge);
+				edge_count = edge_count.saturating_add(EdgeCount::<T>::take(removed.clone()));
+				ensure!(edge_count <= max_edges, Error::<T>::TooManyEdges);
+				VoterSet::<T>::remove(removed.clone());
+				// Edges into a removed member go with them. They were admitted, so no longer pending
+				for voucher in Vouchers::<T>::take(removed.clone()) {
+					Self::remove_vouchee(&voucher, &removed, false);
+				}
+				MemberRecords::<T>::remove(removed.clone());
+				T::OnMemberRemoved::on_member_removed(&removed);
+				Reattestations::<T>::remove(removed.clone());
+				if let Some(expires_at) = MembershipExpiry::<T>::take(removed.clone()) {
+					ExpiryQueue::<T>::remove(expires_at, removed.clone());
+					Self::uncount_expiry(expires_at);
+				}
+				if slash {
+					Self::slash_vouch_bonds(&removed);
+				}
+				VoucheeCount::<T>::remove(removed.clone());
+				for vouchee in Vouchees::<T>::drain_prefix(removed.clone()).map(|(vouchee, ())| vouchee) {
+					// Pending vouches from a removed member no longer count
+					if let Some(mut vouchers) = VouchedForSet::<T>::get(vouchee.clone()) {
+						vouchers.retain(|voucher| *voucher != removed);
+						if vouchers.is_empty() {
+							VouchedForSet::<T>::remove(vouchee.clone());
+						} else {
+							VouchedForSet::<T>::insert(vouchee.clone(), vouchers);
+						}
+						Self::return_vouch_bond(&removed, &vouchee);
+						continue;
+					}
+					if !VoterSet::<T>::contains_key(vouchee.clone()) {
+						continue;
+					}
+					let mut vouchers = Vouchers::<T>::get(vouchee.clone());
+					vouchers.retain(|voucher| *voucher != removed);
+					// A member left without any voucher had all of their support from removed accounts
+					if cascade && vouchers.is_empty() && !to_remove.contains(&vouchee) {
+						Self::deposit_event(Event::MemberRemovedByCascade(vouchee.clone(), removed.clone()));
+						to_remove.push(vouchee);
+					} else {
+						Vouchers::<T>::insert(vouchee, vouchers);
+					}
+				}
+			}
+			Ok((removed_count, edge_count))
+		}
 	}
 }
 
@@ -155,6 +848,12 @@ impl<T: Config> brads_soft_coupling::IdentityInterface<T::Origin, T::AccountId,
 	}
 
 	fn get_voter_from_set(account_id: T::AccountId) -> Option<()> {
-		Pallet::<T>::get_voter_from_set(account_id)
+		// Members whose membership ran out no longer count, even before on_idle prunes them
+		if Pallet::<T>::is_member(&account_id) { Some(()) } else { None }
+	}
+
+	fn voter_count() -> u32 {
+		// Members whose membership ran out don't count here either
+		Pallet::<T>::live_member_count()
 	}
 }
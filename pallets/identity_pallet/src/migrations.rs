@@ -0,0 +1,143 @@
+// Storage migrations for the identity pallet. Each is run from `on_runtime_upgrade` and only does
+// anything when the on-chain storage version is behind.
+
+use crate::pallet::{
+	BootstrapComplete, Config, EdgeCount, ExpiredCount, ExpiryCounts, ExpiryQueue, MemberRecord, MemberRecords,
+	MembershipExpiry, Pallet, PruneCursor, VoterSet, VouchedForSet, Vouchees, VoucheeCount, Vouchers, VouchersFor,
+};
+use frame_support::{
+	pallet_prelude::*,
+	migration::storage_iter,
+	sp_runtime::traits::Saturating,
+	storage::StoragePrefixedMap,
+	traits::{GetStorageVersion, PalletInfoAccess},
+};
+use frame_support::sp_std::vec::Vec;
+
+// Entries of one of the pallet's maps keyed by account with the non-reversible `Blake2_128` hasher, with their
+// accounts. `frame_system::Account` keys are the same hash followed by the account, so each account is read back
+// from the first system account key after its hash. Entries for accounts without a system account are left out
+fn recover_accounts<T: Config, V: Decode>(item: &[u8]) -> Vec<(T::AccountId, V)> {
+	let account_prefix = frame_system::Account::<T>::final_prefix();
+	let entries: Vec<(Vec<u8>, V)> = storage_iter(<Pallet<T> as PalletInfoAccess>::name().as_bytes(), item).collect();
+	entries.into_iter()
+		.filter_map(|(hash, value)| {
+			let start = [&account_prefix[..], &hash[..]].concat();
+			frame_system::Account::<T>::iter_keys_from(start.clone()).next()
+				.filter(|who| frame_system::Account::<T>::hashed_key_for(who).starts_with(&start))
+				.map(|who| (who, value))
+		})
+		.collect()
+}
+
+pub mod v1 {
+	use super::*;
+
+	/// Version 0 memberships never ran out. Gives every member a full MembershipPeriod from the
+	/// upgrade, and starts pruning from the upgrade block. `VoterSet` can't be iterated by key, so
+	/// members are found through their system account, and a member without one keeps a membership
+	/// that doesn't run out.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			return T::DbWeight::get().reads(1)
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		let expires_at = now.saturating_add(T::MembershipPeriod::get().into());
+		let mut members: u64 = 0;
+		for (who, ()) in recover_accounts::<T, ()>(b"VoterSet") {
+			MembershipExpiry::<T>::insert(&who, expires_at);
+			ExpiryQueue::<T>::insert(expires_at, &who, ());
+			members += 1;
+		}
+		PruneCursor::<T>::put(now);
+		StorageVersion::new(1).put::<Pallet<T>>();
+		// Per member: the voter set key and the system account key after it, then the expiry and its queue entry
+		let voters = VoterSet::<T>::count() as u64;
+		T::DbWeight::get().reads_writes(2 + voters * 2, 2 + members * 2)
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Version 1 kept pending vouches only in `VouchedForSet`, and didn't record who vouched a member
+	/// in, when members joined or whether they were originators. Backfills a `Vouchees` edge for every
+	/// pending vouch first, then records every member as joining at the upgrade block, and as an
+	/// originator if they have no `Vouchers`.
+	///
+	/// Version 1 dropped a member's vouchers once they joined, so there are none to backfill and
+	/// every member ends up recorded as an originator. Up to MinVouches members that is right, since
+	/// nobody could be vouched in before then. Past that only MinVouches of them were originators, but
+	/// which ones can't be told, and recording them all keeps every member reachable from an
+	/// originator. Like `VoterSet`, `VouchedForSet` can't be iterated by key, so vouchees and members
+	/// without a system account are missed.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 2 {
+			return T::DbWeight::get().reads(1)
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		let pending = recover_accounts::<T, VouchersFor<T>>(b"VouchedForSet");
+		let mut edges: u64 = 0;
+		for (vouchee, vouchers) in pending.iter() {
+			for voucher in vouchers.iter() {
+				Vouchees::<T>::insert(voucher, vouchee, ());
+				edges += 1;
+			}
+		}
+		let mut members: u64 = 0;
+		for (who, ()) in recover_accounts::<T, ()>(b"VoterSet") {
+			let originator = Vouchers::<T>::get(&who).is_empty();
+			MemberRecords::<T>::insert(&who, MemberRecord { joined_at: now, originator });
+			members += 1;
+		}
+		StorageVersion::new(2).put::<Pallet<T>>();
+		// Per pending vouchee and per member: their entry and the system account key after it. Then an edge per
+		// pending vouch, and per member their vouchers and the record
+		let pending = pending.len() as u64;
+		let voters = VoterSet::<T>::count() as u64;
+		T::DbWeight::get().reads_writes(2 + pending * 2 + voters * 2 + members, 1 + edges + members)
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Version 2 didn't keep the counts behind the live member count, the MaxVouchees bound or the end of the
+	/// bootstrap. Counts every membership into `ExpiryCounts`, or `ExpiredCount` if it has already run out, every vouch
+	/// edge into `EdgeCount`, and the edges whose vouchee is still pending into `VoucheeCount`. Members already past
+	/// MaxVouchees keep their pending vouches but can't vouch again until they are back under it. Marks the bootstrap complete if the voter set has MinVouches members now. A chain whose
+	/// voter set reached MinVouches and then shrank can't be told apart from one that never got there, and stays open
+	/// to originators until it is back at MinVouches.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 3 {
+			return T::DbWeight::get().reads(1)
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		let mut members: u64 = 0;
+		let mut expired: u32 = 0;
+		for (_, expires_at) in MembershipExpiry::<T>::iter() {
+			// on_initialize hasn't run for this block yet, so memberships running out now are still to be counted by it
+			if expires_at < now {
+				expired += 1;
+			} else {
+				ExpiryCounts::<T>::mutate(expires_at, |count| *count += 1);
+			}
+			members += 1;
+		}
+		ExpiredCount::<T>::put(expired);
+		let mut edges: u64 = 0;
+		for (voucher, vouchee) in Vouchees::<T>::iter_keys() {
+			if VouchedForSet::<T>::contains_key(vouchee) {
+				VoucheeCount::<T>::mutate(voucher.clone(), |count| *count += 1);
+			}
+			EdgeCount::<T>::mutate(voucher, |count| *count += 1);
+			edges += 1;
+		}
+		if VoterSet::<T>::count() >= T::MinVouches::get() {
+			BootstrapComplete::<T>::put(true);
+		}
+		StorageVersion::new(3).put::<Pallet<T>>();
+		// Per membership its expiry and count, per edge the edge, its vouchee's pending vouches and its voucher's counts
+		T::DbWeight::get().reads_writes(3 + members * 2 + edges * 4, 3 + members + edges * 2)
+	}
+}
@@ -1,10 +1,11 @@
 use crate::{self as identity_pallet };
-use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use frame_support::{parameter_types, traits::{ConstU16, ConstU32, ConstU64, ConstU128, Hooks}};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -18,6 +19,7 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances,
 		IdentityPallet: identity_pallet,
 	}
 );
@@ -40,7 +42,7 @@ impl system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u128>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -49,15 +51,48 @@ impl system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_balances::Config for Test {
+	type MaxLocks = ConstU32<50>;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u128;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU128<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub static ReattestationVouches: u32 = 0;
+	pub static VouchBond: u128 = 0;
+	pub BondSlash: Perbill = Perbill::from_percent(50);
+}
+
 impl identity_pallet::Config for Test {
 	type Event = Event;
 	type MinVouches = ConstU32<2>;
+	type RemoveOrigin = frame_system::EnsureRoot<u64>;
+	type MembershipPeriod = ConstU32<100>;
+	type ReattestationVouches = ReattestationVouches;
+	type Currency = Balances;
+	type VouchBond = VouchBond;
+	type BondMaturity = ConstU32<50>;
+	type BondSlash = BondSlash;
+	type Slash = ();
+	type OnMemberRemoved = ();
+	type MaxVouchees = ConstU32<3>;
 }
 
 pub struct ExtBuilder;
 impl ExtBuilder {
 	pub fn build() -> sp_io::TestExternalities {
-	 let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	 let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	 pallet_balances::GenesisConfig::<Test> {
+	  balances: vec![(1, 10_000), (2, 10_000), (3, 10_000), (4, 100)],
+	 }
+	  .assimilate_storage(&mut t)
+	  .unwrap();
 	 let mut ext = sp_io::TestExternalities::new(t);
 	 ext.execute_with(|| System::set_block_number(2));
 	 ext
@@ -69,5 +104,7 @@ pub fn run_to_block(n: u64) {
 		let head = System::finalize();
 		System::set_block_number(System::block_number() + 1);
 		System::initialize(&System::block_number(), &head.parent_hash, &head.digest);
+		IdentityPallet::on_initialize(System::block_number());
+		IdentityPallet::on_idle(System::block_number(), u64::MAX);
 	}
 }
\ No newline at end of file
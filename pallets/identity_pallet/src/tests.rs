@@ -1,5 +1,5 @@
 use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::{Currency, GenesisBuild, GetStorageVersion, Hooks, StorageVersion}};
 
 #[test]
 fn can_seed_original_voters() {
@@ -58,4 +58,365 @@ fn vouch_fail_situations() {
 		assert_ok!(IdentityPallet::vouch_for(origin2.clone(), 3));
 		assert_eq!(IdentityPallet::get_voter_from_set(3), Some(()));
 	});
-}
\ No newline at end of file
+}
+#[test]
+fn pending_vouches_can_be_revoked() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 3));
+		assert_noop!(IdentityPallet::revoke_vouch(origin2.clone(), 3), Error::<Test>::NotVouchedFor);
+		assert_ok!(IdentityPallet::revoke_vouch(origin.clone(), 3));
+		System::assert_last_event(Event::IdentityPallet(crate::Event::VouchRevoked(1, 3)));
+		assert_noop!(IdentityPallet::revoke_vouch(origin.clone(), 3), Error::<Test>::NotVouchedFor);
+		// With the first vouch gone, one more is not enough to join
+		assert_ok!(IdentityPallet::vouch_for(origin2.clone(), 3));
+		assert_eq!(IdentityPallet::get_voter_from_set(3), None);
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 3));
+		assert_eq!(IdentityPallet::get_voter_from_set(3), Some(()));
+		// Vouches that already brought someone into the voter set can't be taken back
+		assert_noop!(IdentityPallet::revoke_vouch(origin.clone(), 3), Error::<Test>::NotVouchedFor);
+	});
+}
+
+#[test]
+fn members_can_be_removed_with_cascade() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		let origin3 = Origin::signed(3);
+		let origin4 = Origin::signed(4);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 3));
+		assert_ok!(IdentityPallet::vouch_for(origin2.clone(), 3));
+		assert_ok!(IdentityPallet::vouch_for(origin3.clone(), 4));
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 4));
+		assert_ok!(IdentityPallet::vouch_for(origin3.clone(), 5));
+		assert_ok!(IdentityPallet::vouch_for(origin4.clone(), 5));
+		assert_ok!(IdentityPallet::vouch_for(origin3.clone(), 6));
+		assert_eq!(IdentityPallet::get_vouchers(4).to_vec(), vec![3, 1]);
+		assert_noop!(IdentityPallet::remove_member(origin.clone(), 3, false, 0, 10), sp_runtime::DispatchError::BadOrigin);
+		assert_noop!(IdentityPallet::remove_member(Origin::root(), 6, false, 0, 10), Error::<Test>::NotInVoterSet);
+		// 4 and 5 still have a voucher left, and 3's pending vouch for 6 is dropped
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 3, false, 0, 10));
+		System::assert_last_event(Event::IdentityPallet(crate::Event::MemberRemoved(3)));
+		assert_eq!(IdentityPallet::get_voter_from_set(3), None);
+		assert_eq!(IdentityPallet::get_vouchers(4).to_vec(), vec![1]);
+		assert_eq!(IdentityPallet::get_vouchers(5).to_vec(), vec![4]);
+		assert_noop!(IdentityPallet::revoke_vouch(origin3.clone(), 6), Error::<Test>::NotVouchedFor);
+		assert_noop!(IdentityPallet::vouch_for(origin3.clone(), 6), Error::<Test>::VoucherNotInVoterSet);
+		// Removing 1 leaves 4 without support, and then 5, which is more than a max_cascade of 1 allows
+		assert_noop!(IdentityPallet::remove_member(Origin::root(), 1, true, 1, 2), Error::<Test>::CascadeTooLarge);
+		// On the way it walks 1's edge to 4 and 4's edge to 5
+		assert_eq!(IdentityPallet::get_edge_count(1), 1);
+		assert_noop!(IdentityPallet::remove_member(Origin::root(), 1, true, 2, 1), Error::<Test>::TooManyEdges);
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 1, true, 2, 2));
+		System::assert_has_event(Event::IdentityPallet(crate::Event::MemberRemoved(1)));
+		System::assert_has_event(Event::IdentityPallet(crate::Event::MemberRemovedByCascade(4, 1)));
+		System::assert_has_event(Event::IdentityPallet(crate::Event::MemberRemovedByCascade(5, 4)));
+		assert_eq!(IdentityPallet::get_voter_from_set(4), None);
+		assert_eq!(IdentityPallet::get_voter_from_set(5), None);
+		assert_eq!(IdentityPallet::get_voter_from_set(2), Some(()));
+		assert_eq!(crate::pallet::VoterSet::<Test>::count(), 1);
+	});
+}
+
+#[test]
+fn memberships_expire_unless_renewed() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		assert_eq!(IdentityPallet::get_membership_expiry(2), Some(102));
+		run_to_block(50);
+		assert_ok!(IdentityPallet::reattest(origin.clone()));
+		System::assert_last_event(Event::IdentityPallet(crate::Event::MembershipRenewed(1, 150)));
+		assert_noop!(IdentityPallet::reattest(Origin::signed(3)), Error::<Test>::NotInVoterSet);
+		run_to_block(101);
+		assert_eq!(IdentityPallet::get_voter_from_set(2), Some(()));
+		// Pruned in on_idle of the block the membership runs out
+		run_to_block(102);
+		System::assert_has_event(Event::IdentityPallet(crate::Event::MembershipExpired(2)));
+		assert_eq!(IdentityPallet::get_voter_from_set(2), None);
+		assert_eq!(IdentityPallet::get_membership_expiry(2), None);
+		assert_noop!(IdentityPallet::vouch_for(origin2.clone(), 3), Error::<Test>::VoucherNotInVoterSet);
+		assert_eq!(IdentityPallet::get_voter_from_set(1), Some(()));
+		run_to_block(150);
+		assert_eq!(IdentityPallet::get_voter_from_set(1), None);
+	});
+}
+
+#[test]
+fn expired_members_stop_counting_before_they_are_pruned() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(2)));
+		run_to_block(50);
+		assert_ok!(IdentityPallet::reattest(origin.clone()));
+		run_to_block(101);
+		// Only on_initialize runs in block 102, so 2 has run out without being pruned
+		System::set_block_number(102);
+		IdentityPallet::on_initialize(102);
+		assert_eq!(crate::pallet::VoterSet::<Test>::count(), 2);
+		assert_eq!((IdentityPallet::live_member_count(), IdentityPallet::get_expired_count()), (1, 1));
+		// Only on_idle takes an expired member out, so they can't be vouched back in until then
+		assert_noop!(IdentityPallet::vouch_for(origin.clone(), 2), Error::<Test>::ExpiredMemberNotPruned);
+		IdentityPallet::on_idle(102, u64::MAX);
+		assert_eq!(crate::pallet::VoterSet::<Test>::count(), 1);
+		assert_eq!((IdentityPallet::live_member_count(), IdentityPallet::get_expired_count()), (1, 0));
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 2));
+	});
+}
+
+#[test]
+fn originator_admission_closes_for_good() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(1)));
+		assert!(!IdentityPallet::get_bootstrap_complete());
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(2)));
+		assert!(IdentityPallet::get_bootstrap_complete());
+		// Shrinking the voter set again doesn't reopen it
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 2, false, 0, 10));
+		assert_noop!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(3)), Error::<Test>::NoNeedForAdditionalOriginators);
+	});
+}
+
+#[test]
+fn vouchees_are_capped() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(2)));
+		for who in 3..=5u64 {
+			assert_ok!(IdentityPallet::vouch_for(origin.clone(), who));
+		}
+		assert_noop!(IdentityPallet::vouch_for(origin.clone(), 6), Error::<Test>::TooManyVouchees);
+		// Revoking a vouch makes room, and so does a vouchee being removed
+		assert_ok!(IdentityPallet::revoke_vouch(origin.clone(), 5));
+		assert_ok!(IdentityPallet::vouch_for(Origin::signed(2), 3));
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 3, false, 0, 10));
+		assert_eq!(IdentityPallet::get_vouchee_count(1), 1);
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 6));
+		assert_eq!(IdentityPallet::get_vouchee_count(1), 2);
+	});
+}
+
+#[test]
+fn vouchers_can_admit_more_than_max_vouchees_over_time() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		// MaxVouchees is 3, but only pending vouches count against it
+		for who in 3..=7u64 {
+			assert_ok!(IdentityPallet::vouch_for(origin.clone(), who));
+			assert_ok!(IdentityPallet::vouch_for(origin2.clone(), who));
+			assert_eq!(IdentityPallet::get_voter_from_set(who), Some(()));
+		}
+		assert_eq!(IdentityPallet::get_vouchee_count(1), 0);
+		assert_eq!(IdentityPallet::get_edge_count(1), 5);
+		let mut vouchees = IdentityPallet::vouchees_of(&1);
+		vouchees.sort();
+		assert_eq!(vouchees, vec![3, 4, 5, 6, 7]);
+		// Removing 1 walks all five edges
+		assert_noop!(IdentityPallet::remove_member(Origin::root(), 1, false, 0, 4), Error::<Test>::TooManyEdges);
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 1, false, 0, 5));
+		assert_eq!(IdentityPallet::get_vouchers(3).to_vec(), vec![2]);
+	});
+}
+
+#[test]
+fn memberships_can_be_renewed_by_vouches() {
+	ExtBuilder::build().execute_with(|| {
+		ReattestationVouches::set(&2);
+		let origin = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		let origin3 = Origin::signed(3);
+		let origin4 = Origin::signed(4);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 3));
+		assert_ok!(IdentityPallet::vouch_for(origin2.clone(), 3));
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 4));
+		assert_ok!(IdentityPallet::vouch_for(origin2.clone(), 4));
+		assert_noop!(IdentityPallet::reattest(origin3.clone()), Error::<Test>::ReattestationNeedsVouches);
+		run_to_block(60);
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 3));
+		System::assert_last_event(Event::IdentityPallet(crate::Event::ReattestationVouched(1, 3)));
+		assert_noop!(IdentityPallet::vouch_for(origin.clone(), 3), Error::<Test>::VouchedForSameTwice);
+		// Vouching for oneself doesn't count
+		assert_ok!(IdentityPallet::vouch_for(origin3.clone(), 3));
+		assert_eq!(IdentityPallet::get_reattestations(3).to_vec(), vec![1]);
+		assert_ok!(IdentityPallet::vouch_for(origin4.clone(), 3));
+		System::assert_last_event(Event::IdentityPallet(crate::Event::MembershipRenewed(3, 160)));
+		assert_eq!(IdentityPallet::get_reattestations(3).len(), 0);
+		run_to_block(102);
+		assert_eq!(IdentityPallet::get_voter_from_set(3), Some(()));
+		assert_eq!(IdentityPallet::get_voter_from_set(4), None);
+	});
+}
+
+#[test]
+fn migrates_members_to_expiring_memberships() {
+	ExtBuilder::build().execute_with(|| {
+		StorageVersion::new(0).put::<IdentityPallet>();
+		// Members are found through their system account, and 8 doesn't have one
+		Balances::make_free_balance_be(&7, 100);
+		crate::pallet::VoterSet::<Test>::insert(7, ());
+		crate::pallet::VoterSet::<Test>::insert(8, ());
+		// 9's pending vouch from 7 only lived in VouchedForSet
+		Balances::make_free_balance_be(&9, 100);
+		crate::pallet::VouchedForSet::<Test>::insert(9, crate::pallet::VouchersFor::<Test>::try_from(vec![7]).unwrap());
+		IdentityPallet::on_runtime_upgrade();
+		assert_eq!(IdentityPallet::vouchees_of(&7), vec![9]);
+		assert_eq!(IdentityPallet::get_vouchee_count(7), 1);
+		assert_eq!(IdentityPallet::get_edge_count(7), 1);
+		assert_eq!(IdentityPallet::get_membership_expiry(8), None);
+		assert_eq!(IdentityPallet::on_chain_storage_version(), 3);
+		assert_eq!(IdentityPallet::get_membership_expiry(7), Some(102));
+		assert_eq!(IdentityPallet::get_member_record(7), Some(crate::MemberRecord { joined_at: 2, originator: true }));
+		// Two members is MinVouches, so the bootstrap is over
+		assert!(IdentityPallet::get_bootstrap_complete());
+		run_to_block(101);
+		assert_eq!(IdentityPallet::live_member_count(), 2);
+		run_to_block(102);
+		assert_eq!(IdentityPallet::get_voter_from_set(7), None);
+		assert_eq!(IdentityPallet::get_voter_from_set(8), Some(()));
+		assert_eq!((IdentityPallet::live_member_count(), IdentityPallet::get_expired_count()), (1, 0));
+	});
+}
+
+#[test]
+fn vouches_are_bonded_and_slashed_on_removal() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		VouchBond::set(&20_000);
+		assert_noop!(IdentityPallet::vouch_for(origin.clone(), 3), Error::<Test>::InsufficientVouchBond);
+		VouchBond::set(&1_000);
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 3));
+		System::assert_has_event(Event::IdentityPallet(crate::Event::VouchBonded(1, 3, 1_000)));
+		assert_eq!(IdentityPallet::get_vouch_bond(3, 1), Some(crate::VouchBondInfo { amount: 1_000, matures_at: 52 }));
+		assert_ok!(IdentityPallet::vouch_for(origin2.clone(), 3));
+		assert_noop!(IdentityPallet::release_vouch_bond(origin.clone(), 3), Error::<Test>::VouchBondNotMatured);
+		assert_noop!(IdentityPallet::release_vouch_bond(origin.clone(), 4), Error::<Test>::NoVouchBond);
+		// Revoking a pending vouch returns its bond
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 5));
+		assert_eq!(Balances::reserved_balance(1), 2_000);
+		assert_ok!(IdentityPallet::revoke_vouch(origin.clone(), 5));
+		System::assert_has_event(Event::IdentityPallet(crate::Event::VouchBondReleased(1, 5, 1_000)));
+		assert_eq!(Balances::reserved_balance(1), 1_000);
+		// Removing 3 before the bonds mature slashes BondSlash of each
+		run_to_block(10);
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 3, false, 0, 10));
+		System::assert_has_event(Event::IdentityPallet(crate::Event::VouchBondSlashed(1, 3, 500)));
+		System::assert_has_event(Event::IdentityPallet(crate::Event::VouchBondSlashed(2, 3, 500)));
+		assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (9_500, 0));
+		assert_eq!(IdentityPallet::get_vouch_bond(3, 1), None);
+		// Matured bonds are released in full, whether by their voucher or on removal
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 4));
+		assert_ok!(IdentityPallet::vouch_for(origin2.clone(), 4));
+		run_to_block(60);
+		assert_ok!(IdentityPallet::release_vouch_bond(origin.clone(), 4));
+		assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (9_500, 0));
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 4, false, 0, 10));
+		System::assert_has_event(Event::IdentityPallet(crate::Event::VouchBondReleased(2, 4, 1_000)));
+		assert_eq!((Balances::free_balance(2), Balances::reserved_balance(2)), (9_500, 0));
+	});
+}
+
+fn genesis_with(members: Vec<u64>, vouches: Vec<(u64, u64)>) -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	crate::GenesisConfig::<Test> { members, vouches }.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(2));
+	ext
+}
+
+#[test]
+fn genesis_seeds_members_and_vouches() {
+	genesis_with(vec![1, 2], vec![(1, 3)]).execute_with(|| {
+		assert_eq!(IdentityPallet::get_voter_from_set(1), Some(()));
+		assert_eq!(IdentityPallet::get_membership_expiry(2), Some(100));
+		// Genesis members count towards MinVouches, so nobody can race to become an originator
+		assert_noop!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(4)), Error::<Test>::NoNeedForAdditionalOriginators);
+		assert_ok!(IdentityPallet::vouch_for(Origin::signed(2), 3));
+		assert_eq!(IdentityPallet::get_voter_from_set(3), Some(()));
+		assert_eq!(IdentityPallet::get_vouchers(3).to_vec(), vec![1, 2]);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Genesis vouches must come from genesis members")]
+fn genesis_rejects_vouches_from_non_members() {
+	genesis_with(vec![1, 2], vec![(3, 4)]);
+}
+
+#[test]
+#[should_panic(expected = "Genesis vouchee would reach MinVouches")]
+fn genesis_rejects_vouchees_reaching_min_vouches() {
+	genesis_with(vec![1, 2], vec![(1, 3), (2, 3)]);
+}
+
+#[test]
+fn root_can_force_add_members() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 3));
+		assert_noop!(IdentityPallet::force_add_member(origin.clone(), 3), sp_runtime::DispatchError::BadOrigin);
+		assert_noop!(IdentityPallet::force_add_member(Origin::root(), 1), Error::<Test>::AlreadyInSet);
+		assert_ok!(IdentityPallet::force_add_member(Origin::root(), 3));
+		System::assert_last_event(Event::IdentityPallet(crate::Event::MemberForceAdded(3)));
+		assert_eq!(IdentityPallet::get_voter_from_set(3), Some(()));
+		assert_eq!(IdentityPallet::get_vouchers(3).to_vec(), vec![1]);
+		assert_eq!(IdentityPallet::get_membership_expiry(3), Some(102));
+		assert_noop!(IdentityPallet::revoke_vouch(origin.clone(), 3), Error::<Test>::NotVouchedFor);
+	});
+}
+
+#[test]
+fn vouch_graph_can_be_queried() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		let origin3 = Origin::signed(3);
+		let origin4 = Origin::signed(4);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		run_to_block(5);
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 3));
+		assert_ok!(IdentityPallet::vouch_for(origin2.clone(), 3));
+		assert_ok!(IdentityPallet::vouch_for(origin3.clone(), 4));
+		assert_ok!(IdentityPallet::vouch_for(origin.clone(), 4));
+		assert_ok!(IdentityPallet::vouch_for(origin3.clone(), 5));
+		assert_ok!(IdentityPallet::vouch_for(origin4.clone(), 5));
+		assert_ok!(IdentityPallet::vouch_for(Origin::signed(5), 6));
+		assert_eq!(IdentityPallet::get_member_record(1), Some(crate::MemberRecord { joined_at: 2, originator: true }));
+		assert_eq!(IdentityPallet::get_member_record(3), Some(crate::MemberRecord { joined_at: 5, originator: false }));
+		// Edges are kept after their vouchee has joined
+		assert_eq!(IdentityPallet::vouchers_of(&5), vec![3, 4]);
+		let mut vouchees = IdentityPallet::vouchees_of(&3);
+		vouchees.sort();
+		assert_eq!(vouchees, vec![4, 5]);
+		assert_eq!(IdentityPallet::vouchees_of(&5), vec![6]);
+		assert_eq!(IdentityPallet::path_to_originator(&5), Some(vec![5, 3, 1]));
+		assert_eq!(IdentityPallet::path_to_originator(&1), Some(vec![1]));
+		assert_eq!(IdentityPallet::path_to_originator(&6), None);
+		assert_eq!(IdentityPallet::member_depths(), vec![(1, 0), (2, 0), (3, 1), (4, 1), (5, 2)]);
+		// Paths route around removed members
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 3, false, 0, 10));
+		assert_eq!(IdentityPallet::get_member_record(3), None);
+		assert_eq!(IdentityPallet::vouchees_of(&1), vec![4]);
+		assert_eq!(IdentityPallet::path_to_originator(&5), Some(vec![5, 4, 1]));
+		assert_eq!(IdentityPallet::member_depths(), vec![(1, 0), (2, 0), (4, 1), (5, 2)]);
+	});
+}
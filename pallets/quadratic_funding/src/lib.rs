@@ -0,0 +1,375 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Quadratic funding grant rounds. Projects register for the open round and anyone can contribute to them, but only
+/// contributions from members of the identity pallet's voter set are matched. When the round ends, the matching pool is
+/// split between projects in proportion to the square of the sum of the square roots of their matched contributions.
+/// Learn more about FRAME and the core library of Substrate FRAME pallets:
+/// <https://docs.substrate.io/v3/runtime/frame>
+pub use pallet::*;
+
+pub mod weights;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{pallet_prelude::*, traits::{Currency, ExistenceRequirement}, PalletId};
+	use frame_support::sp_runtime::{traits::{AccountIdConversion, IntegerSquareRoot, Saturating, UniqueSaturatedInto, Zero}, Perbill};
+	use frame_system::pallet_prelude::*;
+	use sp_std::vec::Vec;
+	use brads_soft_coupling::IdentityInterface;
+	use crate::weights::WeightInfo;
+
+	pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type RoundIndex = u32;
+	pub type ProjectIndex = u32;
+
+	// The grant round currently taking projects and contributions
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct RoundInfo<BlockNumber> {
+		pub index: RoundIndex,
+		pub start: BlockNumber,
+		pub end: BlockNumber,
+	}
+
+	// A project registered for the open round
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ProjectInfo<T: Config> {
+		pub owner: T::AccountId,
+		pub name: BoundedVec<u8, T::MaxNameLength>,
+		// Everything contributed, matched or not
+		pub raised: BalanceOf<T>,
+		// Sum of the square roots of the matched parts of its contributions
+		pub sqrt_sum: u128,
+		// Number of accounts that have contributed, kept within MaxContributors
+		pub contributors: u32,
+	}
+
+	// What one account has contributed to one project
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Contribution<Balance> {
+		pub amount: Balance,
+		// The part of the amount given while the contributor was in the voter set, which is all that counts towards
+		// matching
+		pub matched: Balance,
+	}
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency contributions and matching funds are paid in
+		type Currency: Currency<Self::AccountId>;
+		/// Interface to the identity pallet, used to decide which contributions are matched
+		type Identity: IdentityInterface<Self::Origin, Self::AccountId, DispatchResult>;
+		/// Origin allowed to start a new grant round
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+		/// The pallet id whose account holds contributions and the matching pool until payout
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// Configurable constant for the length in blocks of a grant round
+		#[pallet::constant]
+		type RoundLength: Get<u32>;
+		/// Configurable constant for the number of projects that can register for one round
+		#[pallet::constant]
+		type MaxProjects: Get<u32>;
+		/// Configurable constant for the length of a project name
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+		/// Configurable constant for the number of accounts that can contribute to one project. Bounds the
+		/// contributions cleared for each project when a round ends
+		#[pallet::constant]
+		type MaxContributors: Get<u32>;
+		/// Weight information for extrinsics and hooks in this pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	// The struct on which we build all of our pallet logic
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	// Pallet storage items
+	#[pallet::storage]
+	#[pallet::getter(fn get_round)]
+	/// The open grant round, if there is one
+	pub(super) type CurrentRound<T: Config> = StorageValue<_, RoundInfo<T::BlockNumber>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_round_count)]
+	/// The number of grant rounds ever started
+	pub(super) type RoundCount<T> = StorageValue<_, RoundIndex, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_matching_pool)]
+	/// Funds waiting to be split between projects at the end of the next round
+	pub(super) type MatchingPool<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_project_count)]
+	/// The number of projects ever registered. Also the index of the next project
+	pub(super) type ProjectCount<T> = StorageValue<_, ProjectIndex, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_project)]
+	/// Projects registered for the open round. Removed once they are paid out
+	pub(super) type Projects<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, ProjectInfo<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_round_projects)]
+	/// Indices of the projects registered for the open round
+	pub(super) type RoundProjects<T: Config> = StorageValue<_, BoundedVec<ProjectIndex, T::MaxProjects>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_contribution)]
+	/// Contributions to each project of the open round
+	pub(super) type Contributions<T: Config> = StorageDoubleMap<_, Twox64Concat, ProjectIndex, Blake2_128Concat, T::AccountId, Contribution<BalanceOf<T>>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_unclaimed_payout)]
+	/// Payouts that couldn't be transferred when their round ended, as (owner, contributions, matching). They stay
+	/// in the pallet account until claimed with `claim_payout`
+	pub(super) type UnclaimedPayouts<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, (T::AccountId, BalanceOf<T>, BalanceOf<T>), OptionQuery>;
+
+	// Pallets events
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A grant round was started [round, end]
+		RoundStarted(RoundIndex, T::BlockNumber),
+		/// A project registered for the open round [project, owner]
+		ProjectRegistered(ProjectIndex, T::AccountId),
+		/// Tokens were contributed to a project [contributor, project, amount, matched]
+		Contributed(T::AccountId, ProjectIndex, BalanceOf<T>, bool),
+		/// Tokens were added to the matching pool [funder, amount]
+		MatchingPoolFunded(T::AccountId, BalanceOf<T>),
+		/// A project was paid its contributions and its share of the matching pool [project, owner, contributions, matching]
+		ProjectPaid(ProjectIndex, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// A project's payout couldn't be transferred and was kept for a later claim [project, owner, contributions, matching]
+		PayoutFailed(ProjectIndex, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// A grant round ended [round, matchingPaid]
+		RoundFinalized(RoundIndex, BalanceOf<T>),
+	}
+
+	// Pallet Errors
+	#[pallet::error]
+	pub enum Error<T> {
+		/// A grant round is already open
+		RoundAlreadyOpen,
+		/// There's no grant round open
+		NoOpenRound,
+		/// The open round has MaxProjects projects already
+		TooManyProjects,
+		/// The project name is longer than MaxNameLength
+		NameTooLong,
+		/// There's no project with this index in the open round
+		UnknownProject,
+		/// Contributions and matching funds must be more than zero
+		ZeroAmount,
+		/// The project has MaxContributors contributors already
+		TooManyContributors,
+		/// There's no unclaimed payout for this project
+		NoUnclaimedPayout,
+		/// Only the project owner or AdminOrigin can claim a payout
+		NotProjectOwner,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// Pays out the open round once it reaches its end
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			match CurrentRound::<T>::get() {
+				Some(round) if round.end <= n => Self::finalize_round(round),
+				_ => T::WeightInfo::on_initialize(),
+			}
+		}
+	}
+
+	// Callable extrinsic functions for this pallet
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Opens a grant round that takes projects and contributions for RoundLength blocks.
+		/// Events: RoundStarted
+		/// Errors: RoundAlreadyOpen
+		#[pallet::weight(T::WeightInfo::start_round())]
+		pub fn start_round(origin: OriginFor<T>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Self::start_round_impl()?;
+			Ok(())
+		}
+
+		/// Registers a project owned by the sender for the open round.
+		/// Events: ProjectRegistered
+		/// Errors: NoOpenRound, NameTooLong, TooManyProjects
+		#[pallet::weight(T::WeightInfo::register_project())]
+		pub fn register_project(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::register_project_impl(sender, name)?;
+			Ok(())
+		}
+
+		/// Contributes tokens to a project of the open round. Contributions made from outside the voter set are paid
+		/// to the project but not matched, even once the contributor joins it.
+		/// Events: Contributed
+		/// Errors: ZeroAmount, UnknownProject, TooManyContributors
+		#[pallet::weight(T::WeightInfo::contribute())]
+		pub fn contribute(origin: OriginFor<T>, project: ProjectIndex, amount: BalanceOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::contribute_impl(sender, project, amount)?;
+			Ok(())
+		}
+
+		/// Adds tokens to the matching pool, to be split between projects at the end of the next round.
+		/// Events: MatchingPoolFunded
+		/// Errors: ZeroAmount
+		#[pallet::weight(T::WeightInfo::fund_matching_pool())]
+		pub fn fund_matching_pool(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::fund_matching_pool_impl(sender, amount)?;
+			Ok(())
+		}
+
+		/// Pays a project the payout that couldn't be transferred when its round ended. Can be called by the project
+		/// owner or AdminOrigin, and always pays the owner.
+		/// Events: ProjectPaid
+		/// Errors: NoUnclaimedPayout, NotProjectOwner
+		#[pallet::weight(T::WeightInfo::claim_payout())]
+		pub fn claim_payout(origin: OriginFor<T>, project: ProjectIndex) -> DispatchResult {
+			let sender = match T::AdminOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+			Self::claim_payout_impl(sender, project)?;
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account holding contributions and the matching pool
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		fn start_round_impl() -> Result<(), DispatchError> {
+			ensure!(CurrentRound::<T>::get().is_none(), Error::<T>::RoundAlreadyOpen);
+			let index = RoundCount::<T>::get();
+			let start = frame_system::Pallet::<T>::block_number();
+			let end = start + T::RoundLength::get().into();
+			CurrentRound::<T>::put(RoundInfo { index, start, end });
+			RoundCount::<T>::put(index + 1);
+			Self::deposit_event(Event::RoundStarted(index, end));
+			Ok(())
+		}
+
+		fn register_project_impl(sender: T::AccountId, name: Vec<u8>) -> Result<(), DispatchError> {
+			// Check failure conditions
+			ensure!(CurrentRound::<T>::get().is_some(), Error::<T>::NoOpenRound);
+			let name: BoundedVec<u8, T::MaxNameLength> = name.try_into().map_err(|_| Error::<T>::NameTooLong)?;
+			let index = ProjectCount::<T>::get();
+			RoundProjects::<T>::try_append(index).map_err(|_| Error::<T>::TooManyProjects)?;
+			// Register the project
+			Projects::<T>::insert(index, ProjectInfo { owner: sender.clone(), name, raised: Zero::zero(), sqrt_sum: 0, contributors: 0 });
+			ProjectCount::<T>::put(index + 1);
+			Self::deposit_event(Event::ProjectRegistered(index, sender));
+			Ok(())
+		}
+
+		fn contribute_impl(sender: T::AccountId, project_index: ProjectIndex, amount: BalanceOf<T>) -> Result<(), DispatchError> {
+			// Check failure conditions
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			let mut project = Projects::<T>::get(project_index).ok_or(Error::<T>::UnknownProject)?;
+			let previous = Contributions::<T>::get(project_index, &sender);
+			if previous.is_none() {
+				ensure!(project.contributors < T::MaxContributors::get(), Error::<T>::TooManyContributors);
+				project.contributors += 1;
+			}
+			T::Currency::transfer(&sender, &Self::account_id(), amount, ExistenceRequirement::KeepAlive)?;
+			// Only what's given from inside the voter set is matched. Replace the square root of the earlier matched part
+			// with that of the new one
+			let mut contribution = previous.unwrap_or(Contribution { amount: Zero::zero(), matched: Zero::zero() });
+			contribution.amount = contribution.amount.saturating_add(amount);
+			let matched = T::Identity::get_voter_from_set(sender.clone()).is_some();
+			if matched {
+				project.sqrt_sum = project.sqrt_sum.saturating_sub(Self::sqrt(contribution.matched));
+				contribution.matched = contribution.matched.saturating_add(amount);
+				project.sqrt_sum = project.sqrt_sum.saturating_add(Self::sqrt(contribution.matched));
+			}
+			project.raised = project.raised.saturating_add(amount);
+			Contributions::<T>::insert(project_index, &sender, contribution);
+			Projects::<T>::insert(project_index, project);
+			Self::deposit_event(Event::Contributed(sender, project_index, amount, matched));
+			Ok(())
+		}
+
+		fn fund_matching_pool_impl(sender: T::AccountId, amount: BalanceOf<T>) -> Result<(), DispatchError> {
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			T::Currency::transfer(&sender, &Self::account_id(), amount, ExistenceRequirement::KeepAlive)?;
+			MatchingPool::<T>::mutate(|pool| *pool = pool.saturating_add(amount));
+			Self::deposit_event(Event::MatchingPoolFunded(sender, amount));
+			Ok(())
+		}
+
+		fn claim_payout_impl(sender: Option<T::AccountId>, project_index: ProjectIndex) -> Result<(), DispatchError> {
+			let (owner, raised, matching) = UnclaimedPayouts::<T>::get(project_index).ok_or(Error::<T>::NoUnclaimedPayout)?;
+			if let Some(sender) = sender {
+				ensure!(sender == owner, Error::<T>::NotProjectOwner);
+			}
+			T::Currency::transfer(&Self::account_id(), &owner, raised.saturating_add(matching), ExistenceRequirement::AllowDeath)?;
+			UnclaimedPayouts::<T>::remove(project_index);
+			Self::deposit_event(Event::ProjectPaid(project_index, owner, raised, matching));
+			Ok(())
+		}
+
+		fn sqrt(amount: BalanceOf<T>) -> u128 {
+			amount.integer_sqrt().unique_saturated_into()
+		}
+
+		// Pays every project of the round its contributions plus its share of the matching pool, which is
+		// pool * (Σ sqrt(matched))² / Σ over all projects of (Σ sqrt(matched))². Whatever isn't allotted,
+		// because of rounding or because nothing was matched, stays in the pool for the next round. A payout that
+		// can't be transferred is kept in UnclaimedPayouts, with its matching taken out of the pool
+		fn finalize_round(round: RoundInfo<T::BlockNumber>) -> Weight {
+			CurrentRound::<T>::kill();
+			let project_indices = RoundProjects::<T>::take();
+			let projects: Vec<(ProjectIndex, ProjectInfo<T>)> = project_indices.iter()
+				.filter_map(|index| Projects::<T>::take(index).map(|project| (*index, project)))
+				.collect();
+			let total_weight = projects.iter()
+				.fold(0u128, |total, (_, project)| total.saturating_add(project.sqrt_sum.saturating_mul(project.sqrt_sum)));
+			let pool = MatchingPool::<T>::get();
+			let mut paid: BalanceOf<T> = Zero::zero();
+			let mut allotted: BalanceOf<T> = Zero::zero();
+			let mut contributors: u32 = 0;
+			for (index, project) in projects.iter() {
+				let matching = if total_weight.is_zero() {
+					Zero::zero()
+				} else {
+					Perbill::from_rational(project.sqrt_sum.saturating_mul(project.sqrt_sum), total_weight) * pool
+				};
+				let payout = project.raised.saturating_add(matching);
+				allotted = allotted.saturating_add(matching);
+				// The pot holds every contribution and the pool, so this should only fail if the payout is below the
+				// existential deposit of a new account. The funds then stay in the pot until the owner claims them
+				if T::Currency::transfer(&Self::account_id(), &project.owner, payout, ExistenceRequirement::AllowDeath).is_ok() {
+					paid = paid.saturating_add(matching);
+					Self::deposit_event(Event::ProjectPaid(*index, project.owner.clone(), project.raised, matching));
+				} else {
+					UnclaimedPayouts::<T>::insert(index, (project.owner.clone(), project.raised, matching));
+					Self::deposit_event(Event::PayoutFailed(*index, project.owner.clone(), project.raised, matching));
+				}
+				// Contributions are kept within MaxContributors, so this clears them all
+				let _ = Contributions::<T>::clear_prefix(index, T::MaxContributors::get(), None);
+				contributors = contributors.saturating_add(project.contributors);
+			}
+			MatchingPool::<T>::put(pool.saturating_sub(allotted));
+			Self::deposit_event(Event::RoundFinalized(round.index, paid));
+			T::WeightInfo::finalize_round(projects.len() as u32, contributors)
+		}
+	}
+}
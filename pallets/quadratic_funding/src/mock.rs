@@ -0,0 +1,125 @@
+use crate::{self as quadratic_funding};
+use frame_support::{parameter_types, traits::{ConstU16, ConstU32, ConstU64, ConstU128, Hooks}, PalletId};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		QuadraticFunding: quadratic_funding,
+		IdentityPallet: identity_pallet,
+		Balances: pallet_balances,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ConstU32<50>;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	/// The type for recording an account's balance.
+	type Balance = u128;
+	/// The ubiquitous event type.
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU128<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub BondSlash: Perbill = Perbill::from_percent(100);
+	pub const QuadraticFundingPalletId: PalletId = PalletId(*b"py/qfund");
+}
+
+impl quadratic_funding::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type Identity = IdentityPallet;
+	type AdminOrigin = frame_system::EnsureRoot<u64>;
+	type PalletId = QuadraticFundingPalletId;
+	type RoundLength = ConstU32<100>;
+	type MaxProjects = ConstU32<3>;
+	type MaxNameLength = ConstU32<32>;
+	type MaxContributors = ConstU32<3>;
+	type WeightInfo = ();
+}
+
+impl identity_pallet::Config for Test {
+	type Event = Event;
+	type MinVouches = ConstU32<2>;
+	type RemoveOrigin = frame_system::EnsureRoot<u64>;
+	type MembershipPeriod = ConstU32<1_000>;
+	type ReattestationVouches = ConstU32<0>;
+	type Currency = Balances;
+	type VouchBond = ConstU128<1_000>;
+	type BondMaturity = ConstU32<100>;
+	type BondSlash = BondSlash;
+	type Slash = ();
+	type OnMemberRemoved = ();
+	type MaxVouchees = ConstU32<10>;
+}
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 1_000_000), (2, 1_000_000), (3, 1_000_000), (4, 1_000_000), (5, 1_000_000), (6, 1_000_000)],
+		}
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(2));
+		ext
+	}
+}
+
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		let head = System::finalize();
+		System::set_block_number(System::block_number() + 1);
+		System::initialize(&System::block_number(), &head.parent_hash, &head.digest);
+		QuadraticFunding::on_initialize(System::block_number());
+	}
+}
@@ -0,0 +1,145 @@
+use crate::{mock::*, Contribution, Error};
+use frame_support::{assert_noop, assert_ok, error::BadOrigin};
+
+fn seed_voters() {
+	assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(1)));
+	assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(2)));
+}
+
+#[test]
+fn can_start_round_and_register_projects() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(QuadraticFunding::register_project(Origin::signed(4), b"kitty shelter".to_vec()), Error::<Test>::NoOpenRound);
+		assert_noop!(QuadraticFunding::start_round(Origin::signed(1)), BadOrigin);
+		assert_ok!(QuadraticFunding::start_round(Origin::root()));
+		System::assert_last_event(Event::QuadraticFunding(crate::Event::RoundStarted(0, 102)));
+		assert_noop!(QuadraticFunding::start_round(Origin::root()), Error::<Test>::RoundAlreadyOpen);
+		assert_noop!(QuadraticFunding::register_project(Origin::signed(4), vec![1; 33]), Error::<Test>::NameTooLong);
+		for owner in 4..=6u64 {
+			assert_ok!(QuadraticFunding::register_project(Origin::signed(owner), b"kitty shelter".to_vec()));
+		}
+		assert_noop!(QuadraticFunding::register_project(Origin::signed(4), b"kitty shelter".to_vec()), Error::<Test>::TooManyProjects);
+		assert_eq!(QuadraticFunding::get_project(1).unwrap().owner, 5);
+		assert_eq!(QuadraticFunding::get_round_projects().into_inner(), vec![0, 1, 2]);
+	});
+}
+
+#[test]
+fn only_voter_set_contributions_are_matched() {
+	ExtBuilder::build().execute_with(|| {
+		seed_voters();
+		assert_noop!(QuadraticFunding::contribute(Origin::signed(1), 0, 100), Error::<Test>::UnknownProject);
+		assert_noop!(QuadraticFunding::fund_matching_pool(Origin::signed(6), 0), Error::<Test>::ZeroAmount);
+		assert_ok!(QuadraticFunding::fund_matching_pool(Origin::signed(6), 1_000));
+		assert_eq!(QuadraticFunding::get_matching_pool(), 1_000);
+		assert_ok!(QuadraticFunding::start_round(Origin::root()));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(4), b"kitty shelter".to_vec()));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(5), b"kitty clinic".to_vec()));
+		assert_noop!(QuadraticFunding::contribute(Origin::signed(1), 0, 0), Error::<Test>::ZeroAmount);
+		// Topping up a contribution counts the square root of the new total, not of each payment
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(1), 0, 50));
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(1), 0, 50));
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(2), 0, 100));
+		assert_eq!(QuadraticFunding::get_contribution(0, 1), Some(Contribution { amount: 100, matched: 100 }));
+		assert_eq!(QuadraticFunding::get_project(0).unwrap().sqrt_sum, 20);
+		// A large contribution from outside the voter set goes to the project but isn't matched
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(1), 1, 400));
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(3), 1, 10_000));
+		System::assert_last_event(Event::QuadraticFunding(crate::Event::Contributed(3, 1, 10_000, false)));
+		assert_eq!(QuadraticFunding::get_project(1).unwrap().sqrt_sum, 20);
+		assert_eq!(Balances::free_balance(QuadraticFunding::account_id()), 11_600);
+		// Both projects have (Σ sqrt)² = 400, so they split the pool evenly
+		run_to_block(102);
+		System::assert_has_event(Event::QuadraticFunding(crate::Event::ProjectPaid(0, 4, 200, 500)));
+		System::assert_has_event(Event::QuadraticFunding(crate::Event::ProjectPaid(1, 5, 10_400, 500)));
+		System::assert_last_event(Event::QuadraticFunding(crate::Event::RoundFinalized(0, 1_000)));
+		assert_eq!(Balances::free_balance(4), 1_000_700);
+		assert_eq!(Balances::free_balance(5), 1_010_900);
+		assert_eq!(QuadraticFunding::get_matching_pool(), 0);
+		assert_eq!(QuadraticFunding::get_round(), None);
+		assert_eq!(QuadraticFunding::get_project(0), None);
+		assert_eq!(QuadraticFunding::get_contribution(0, 1), None);
+	});
+}
+
+#[test]
+fn only_amounts_given_from_the_voter_set_are_matched() {
+	ExtBuilder::build().execute_with(|| {
+		seed_voters();
+		assert_ok!(QuadraticFunding::start_round(Origin::root()));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(4), b"kitty shelter".to_vec()));
+		// 3 gives from outside the voter set and then again once vouched in. Only the later payment is matched
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(3), 0, 10_000));
+		assert_ok!(IdentityPallet::vouch_for(Origin::signed(1), 3));
+		assert_ok!(IdentityPallet::vouch_for(Origin::signed(2), 3));
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(3), 0, 100));
+		assert_eq!(QuadraticFunding::get_contribution(0, 3), Some(Contribution { amount: 10_100, matched: 100 }));
+		assert_eq!(QuadraticFunding::get_project(0).unwrap().sqrt_sum, 10);
+		// 1 gives as a member and again after being removed. What was matched stays matched
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(1), 0, 400));
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 1, false, 0, 10));
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(1), 0, 500));
+		assert_eq!(QuadraticFunding::get_contribution(0, 1), Some(Contribution { amount: 900, matched: 400 }));
+		assert_eq!(QuadraticFunding::get_project(0).unwrap().sqrt_sum, 30);
+		assert_eq!(QuadraticFunding::get_project(0).unwrap().raised, 11_000);
+	});
+}
+
+#[test]
+fn unmatched_pool_carries_over() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(QuadraticFunding::fund_matching_pool(Origin::signed(6), 1_000));
+		assert_ok!(QuadraticFunding::start_round(Origin::root()));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(4), b"kitty shelter".to_vec()));
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(3), 0, 100));
+		run_to_block(102);
+		System::assert_has_event(Event::QuadraticFunding(crate::Event::ProjectPaid(0, 4, 100, 0)));
+		assert_eq!(QuadraticFunding::get_matching_pool(), 1_000);
+		// The next round can be started once the last one is paid out
+		assert_ok!(QuadraticFunding::start_round(Origin::root()));
+		assert_eq!(QuadraticFunding::get_round().unwrap().index, 1);
+	});
+}
+
+#[test]
+fn contributors_are_capped() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(QuadraticFunding::start_round(Origin::root()));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(4), b"kitty shelter".to_vec()));
+		for who in 1..=3u64 {
+			assert_ok!(QuadraticFunding::contribute(Origin::signed(who), 0, 100));
+		}
+		assert_noop!(QuadraticFunding::contribute(Origin::signed(5), 0, 100), Error::<Test>::TooManyContributors);
+		// Existing contributors can still top up
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(1), 0, 100));
+		assert_eq!(QuadraticFunding::get_project(0).unwrap().contributors, 3);
+	});
+}
+
+#[test]
+fn failed_payouts_can_be_claimed() {
+	ExtBuilder::build().execute_with(|| {
+		seed_voters();
+		assert_ok!(QuadraticFunding::fund_matching_pool(Origin::signed(6), 1_000));
+		assert_ok!(QuadraticFunding::start_round(Origin::root()));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(4), b"kitty shelter".to_vec()));
+		assert_ok!(QuadraticFunding::contribute(Origin::signed(1), 0, 100));
+		// The pot comes up short, so the transfer fails
+		Balances::make_free_balance_be(&QuadraticFunding::account_id(), 500);
+		run_to_block(102);
+		System::assert_has_event(Event::QuadraticFunding(crate::Event::PayoutFailed(0, 4, 100, 1_000)));
+		System::assert_last_event(Event::QuadraticFunding(crate::Event::RoundFinalized(0, 0)));
+		assert_eq!(QuadraticFunding::get_unclaimed_payout(0), Some((4, 100, 1_000)));
+		// The matching is set aside for the project rather than carried over
+		assert_eq!(QuadraticFunding::get_matching_pool(), 0);
+		assert_noop!(QuadraticFunding::claim_payout(Origin::signed(5), 0), Error::<Test>::NotProjectOwner);
+		assert_noop!(QuadraticFunding::claim_payout(Origin::signed(4), 1), Error::<Test>::NoUnclaimedPayout);
+		assert_noop!(QuadraticFunding::claim_payout(Origin::signed(4), 0), pallet_balances::Error::<Test>::InsufficientBalance);
+		// Root can claim on the owner's behalf once the pot is topped up
+		Balances::make_free_balance_be(&QuadraticFunding::account_id(), 1_100);
+		assert_ok!(QuadraticFunding::claim_payout(Origin::root(), 0));
+		System::assert_last_event(Event::QuadraticFunding(crate::Event::ProjectPaid(0, 4, 100, 1_000)));
+		assert_eq!(Balances::free_balance(4), 1_001_100);
+		assert_eq!(QuadraticFunding::get_unclaimed_payout(0), None);
+	});
+}
@@ -0,0 +1,132 @@
+//! Weights for quadratic_funding
+//!
+//! PLACEHOLDER ESTIMATES. These values were written by hand from the storage each call touches and have not been
+//! measured. The pallet has no benchmarks yet. Replace them with numbers measured on reference hardware before
+//! relying on them on a live chain, and revisit them whenever the pallet's storage access changes.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for quadratic_funding.
+pub trait WeightInfo {
+	fn start_round() -> Weight;
+	fn register_project() -> Weight;
+	fn contribute() -> Weight;
+	fn fund_matching_pool() -> Weight;
+	fn claim_payout() -> Weight;
+	fn on_initialize() -> Weight;
+	fn finalize_round(p: u32, c: u32, ) -> Weight;
+}
+
+/// Hand estimated placeholder weights for quadratic_funding, pending benchmarks run on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: QuadraticFunding CurrentRound (r:1 w:1)
+	// Storage: QuadraticFunding RoundCount (r:1 w:1)
+	fn start_round() -> Weight {
+		(18_204_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: QuadraticFunding CurrentRound (r:1 w:0)
+	// Storage: QuadraticFunding ProjectCount (r:1 w:1)
+	// Storage: QuadraticFunding RoundProjects (r:1 w:1)
+	// Storage: QuadraticFunding Projects (r:0 w:1)
+	fn register_project() -> Weight {
+		(24_617_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: QuadraticFunding Projects (r:1 w:1)
+	// Storage: QuadraticFunding Contributions (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: IdentityPallet VoterSet (r:1 w:0)
+	fn contribute() -> Weight {
+		(46_382_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: System Account (r:2 w:2)
+	// Storage: QuadraticFunding MatchingPool (r:1 w:1)
+	fn fund_matching_pool() -> Weight {
+		(35_119_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: QuadraticFunding UnclaimedPayouts (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn claim_payout() -> Weight {
+		(36_540_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: QuadraticFunding CurrentRound (r:1 w:0)
+	fn on_initialize() -> Weight {
+		(2_513_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: QuadraticFunding CurrentRound (r:1 w:1)
+	// Storage: QuadraticFunding RoundProjects (r:1 w:1)
+	// Storage: QuadraticFunding MatchingPool (r:1 w:1)
+	// Storage: QuadraticFunding Projects (r:1 w:1)
+	// Storage: System Account (r:1 w:2)
+	// Storage: QuadraticFunding UnclaimedPayouts (r:0 w:1)
+	// Storage: QuadraticFunding Contributions (r:0 w:1)
+	fn finalize_round(p: u32, c: u32, ) -> Weight {
+		(20_356_000 as Weight)
+			.saturating_add((41_208_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((1_024_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn start_round() -> Weight {
+		(18_204_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn register_project() -> Weight {
+		(24_617_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn contribute() -> Weight {
+		(46_382_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn fund_matching_pool() -> Weight {
+		(35_119_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn claim_payout() -> Weight {
+		(36_540_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn on_initialize() -> Weight {
+		(2_513_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn finalize_round(p: u32, c: u32, ) -> Weight {
+		(20_356_000 as Weight)
+			.saturating_add((41_208_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((1_024_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+}
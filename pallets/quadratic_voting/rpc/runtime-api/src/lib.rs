@@ -0,0 +1,27 @@
+//! Runtime API definition for the quadratic voting pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use quadratic_voting::{ProposalIndex, ReferendumStatus, VotingPowerInfo};
+
+sp_api::decl_runtime_apis! {
+	pub trait QuadraticVotingApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Every open referendum with its proposer, tally and projected outcome
+		fn open_referenda() -> Vec<ReferendumStatus<AccountId, BlockNumber>>;
+
+		/// Whether an open referendum would pass if it were finalized now
+		fn projected_outcome(index: ProposalIndex) -> Option<bool>;
+
+		/// An account's own voting power and the power delegated to it
+		fn voting_power(who: AccountId) -> VotingPowerInfo;
+
+		/// Blocks left until the next open referendum is finalized
+		fn blocks_until_next_finalization() -> Option<BlockNumber>;
+	}
+}
@@ -0,0 +1,93 @@
+//! RPC interface for the quadratic voting pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use quadratic_voting_rpc_runtime_api::{
+	ProposalIndex, QuadraticVotingApi as QuadraticVotingRuntimeApi, ReferendumStatus, VotingPowerInfo,
+};
+
+#[rpc(client, server)]
+pub trait QuadraticVotingApi<BlockHash, AccountId, BlockNumber> {
+	/// Every open referendum with its proposer, tally and projected outcome
+	#[method(name = "quadraticVoting_openReferenda")]
+	fn open_referenda(&self, at: Option<BlockHash>) -> RpcResult<Vec<ReferendumStatus<AccountId, BlockNumber>>>;
+
+	/// Whether an open referendum would pass if it were finalized now
+	#[method(name = "quadraticVoting_projectedOutcome")]
+	fn projected_outcome(&self, index: ProposalIndex, at: Option<BlockHash>) -> RpcResult<Option<bool>>;
+
+	/// An account's own voting power and the power delegated to it
+	#[method(name = "quadraticVoting_votingPower")]
+	fn voting_power(&self, who: AccountId, at: Option<BlockHash>) -> RpcResult<VotingPowerInfo>;
+
+	/// Blocks left until the next open referendum is finalized
+	#[method(name = "quadraticVoting_blocksUntilNextFinalization")]
+	fn blocks_until_next_finalization(&self, at: Option<BlockHash>) -> RpcResult<Option<BlockNumber>>;
+}
+
+/// Provides the quadratic voting RPC methods by calling into the runtime
+pub struct QuadraticVoting<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> QuadraticVoting<C, Block> {
+	/// Creates a new instance of the QuadraticVoting RPC handler
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error code for failures inside the runtime API call
+const RUNTIME_ERROR: i32 = 1;
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(RUNTIME_ERROR, "Runtime error", Some(format!("{:?}", err)))).into()
+}
+
+impl<C, Block, AccountId, BlockNumber> QuadraticVotingApiServer<<Block as BlockT>::Hash, AccountId, BlockNumber>
+	for QuadraticVoting<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: QuadraticVotingRuntimeApi<Block, AccountId, BlockNumber>,
+	AccountId: Codec + Send + Sync + 'static,
+	BlockNumber: Codec + Send + Sync + 'static,
+	ReferendumStatus<AccountId, BlockNumber>: serde::Serialize,
+	BlockNumber: serde::Serialize,
+	AccountId: serde::de::DeserializeOwned,
+{
+	fn open_referenda(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Vec<ReferendumStatus<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.open_referenda(&at).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn projected_outcome(&self, index: ProposalIndex, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<bool>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.projected_outcome(&at, index).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn voting_power(&self, who: AccountId, at: Option<<Block as BlockT>::Hash>) -> RpcResult<VotingPowerInfo> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.voting_power(&at, who).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn blocks_until_next_finalization(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.blocks_until_next_finalization(&at).map_err(runtime_error_into_rpc_err)
+	}
+}
@@ -0,0 +1,111 @@
+// Rules deciding whether a referendum passes once its voting window closes. The pallet asks its
+// `Config::QuorumRule` whether enough of the electorate took part, then its `Config::ApprovalRule`
+// whether the ayes beat the nays.
+
+use frame_support::{
+	sp_runtime::{traits::IntegerSquareRoot, Perbill},
+	traits::Get,
+	RuntimeDebug,
+};
+use sp_std::marker::PhantomData;
+
+/// Everything the rules get to see about a closed referendum
+#[derive(Clone, Copy, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct TallySummary {
+	/// Voting power cast in favour
+	pub ayes: u128,
+	/// Voting power cast against
+	pub nays: u128,
+	/// Number of accounts that cast a ballot
+	pub voters: u32,
+	/// Most the whole electorate could have cast, whether or not they voted: the voting power of every account holding
+	/// voting reserves, or with voice credits the votes every member's budget buys on one referendum
+	pub electorate: u128,
+	/// Size of the identity pallet's `VoterSet`
+	pub members: u32,
+}
+
+impl TallySummary {
+	/// Total voting power that took part
+	pub fn turnout(&self) -> u128 {
+		self.ayes.saturating_add(self.nays)
+	}
+}
+
+/// Decides whether the ayes of a referendum beat its nays
+pub trait ApprovalRule {
+	fn approved(tally: &TallySummary) -> bool;
+}
+
+/// Decides whether enough of the electorate took part for a referendum's result to count
+pub trait QuorumRule {
+	fn quorum_reached(tally: &TallySummary) -> bool;
+}
+
+/// More ayes than nays
+pub struct SimpleMajority;
+
+impl ApprovalRule for SimpleMajority {
+	fn approved(tally: &TallySummary) -> bool {
+		tally.ayes > tally.nays
+	}
+}
+
+/// Ayes must be more than `Threshold` of the turnout, e.g. 2/3 for a two thirds supermajority
+pub struct SuperMajority<Threshold>(PhantomData<Threshold>);
+
+impl<Threshold: Get<Perbill>> ApprovalRule for SuperMajority<Threshold> {
+	fn approved(tally: &TallySummary) -> bool {
+		tally.ayes > Threshold::get() * tally.turnout()
+	}
+}
+
+/// Positive turnout bias, as in adaptive quorum biasing. A low turnout needs a supermajority of ayes
+/// to pass, which relaxes towards a simple majority as turnout approaches the whole electorate.
+/// Passes when `nays / sqrt(turnout) < ayes / sqrt(electorate)`.
+pub struct PositiveTurnoutBias;
+
+impl ApprovalRule for PositiveTurnoutBias {
+	fn approved(tally: &TallySummary) -> bool {
+		let sqrt_turnout = tally.turnout().integer_sqrt();
+		let sqrt_electorate = tally.electorate.integer_sqrt();
+		if sqrt_turnout == 0 || sqrt_electorate == 0 {
+			return false
+		}
+		tally.ayes.saturating_mul(sqrt_turnout) > tally.nays.saturating_mul(sqrt_electorate)
+	}
+}
+
+/// Negative turnout bias, as in adaptive quorum biasing. A low turnout needs a supermajority of nays
+/// to reject, which relaxes towards a simple majority as turnout approaches the whole electorate.
+/// Passes when `nays / sqrt(electorate) < ayes / sqrt(turnout)`.
+pub struct NegativeTurnoutBias;
+
+impl ApprovalRule for NegativeTurnoutBias {
+	fn approved(tally: &TallySummary) -> bool {
+		let sqrt_turnout = tally.turnout().integer_sqrt();
+		let sqrt_electorate = tally.electorate.integer_sqrt();
+		if sqrt_turnout == 0 || sqrt_electorate == 0 {
+			return false
+		}
+		tally.ayes.saturating_mul(sqrt_electorate) > tally.nays.saturating_mul(sqrt_turnout)
+	}
+}
+
+/// Turnout must be more than a fixed amount of voting power
+pub struct AbsolutePower<Threshold>(PhantomData<Threshold>);
+
+impl<Threshold: Get<u128>> QuorumRule for AbsolutePower<Threshold> {
+	fn quorum_reached(tally: &TallySummary) -> bool {
+		tally.turnout() > Threshold::get()
+	}
+}
+
+/// At least `Share` of the identity pallet's `VoterSet` must have voted
+pub struct MemberTurnout<Share>(PhantomData<Share>);
+
+impl<Share: Get<Perbill>> QuorumRule for MemberTurnout<Share> {
+	fn quorum_reached(tally: &TallySummary) -> bool {
+		tally.voters > 0 && tally.voters >= Share::get().mul_ceil(tally.members)
+	}
+}
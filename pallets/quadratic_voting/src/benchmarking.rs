@@ -0,0 +1,338 @@
+//! Benchmarking setup for quadratic-voting
+
+use super::*;
+
+use crate::Pallet as QuadraticVoting;
+use crate::pallet::{EnactmentBlock, Enactments, Referenda};
+use codec::Encode;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{sp_runtime::traits::Hash, traits::{Currency, EnsureOrigin, Hooks}};
+use frame_system::RawOrigin;
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+// Upper bound for the number of archive records pruned in one block in the benchmarks below
+const MAX_ARCHIVED: u32 = 100;
+// Reserve giving each voter a power of 1_000
+const RESERVE: u32 = 1_000_000;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+// Funds an account and has root add it to the voter set. Works whatever members the chain spec seeds, unlike joining
+// as an originator
+fn member<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	T::Token::make_free_balance_be(&who, CurrencyAmount::<T>::from(u32::MAX));
+	T::BenchmarkHelper::add_member(&who);
+	who
+}
+
+// A member holding RESERVE as voting funds
+fn voter<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who = member::<T>(name, index);
+	QuadraticVoting::<T>::reserve_voting_power(RawOrigin::Signed(who.clone()).into(), RESERVE.into()).unwrap();
+	who
+}
+
+fn open_referendum<T: Config>(proposer: &T::AccountId) -> ProposalIndex {
+	QuadraticVoting::<T>::add_proposal(RawOrigin::Signed(proposer.clone()).into(), vec![1; 32], None, None).unwrap();
+	QuadraticVoting::<T>::get_referendum_count() - 1
+}
+
+// Opens a referendum and has `v` voters vote on it
+fn voted_referendum<T: Config>(proposer: &T::AccountId, v: u32) -> ProposalIndex {
+	let index = open_referendum::<T>(proposer);
+	for i in 0..v {
+		let who = voter::<T>("voter", i);
+		QuadraticVoting::<T>::vote(RawOrigin::Signed(who).into(), index, true).unwrap();
+	}
+	index
+}
+
+// Opens a referendum taking secret ballots, whatever Config::SecretBallots says
+fn secret_referendum<T: Config>(proposer: &T::AccountId) -> ProposalIndex {
+	let index = open_referendum::<T>(proposer);
+	Referenda::<T>::mutate(index, |referendum| {
+		if let Some(referendum) = referendum {
+			referendum.secret = true;
+		}
+	});
+	index
+}
+
+fn commitment<T: Config>(who: &T::AccountId, verdict: bool) -> T::Hash {
+	T::Hashing::hash_of(&(who, verdict, [0u8; 32]))
+}
+
+// Moves to the first block of the reveal phase
+fn start_reveal_phase<T: Config>() {
+	let now = frame_system::Pallet::<T>::block_number();
+	frame_system::Pallet::<T>::set_block_number(now + (T::BlocksPerVote::get() / 2).into());
+}
+
+benchmarks! {
+	add_proposal {
+		let b in 0 .. T::MaxProposalLength::get() - 8;
+		let caller = member::<T>("caller", 0);
+		// Worst case is appending to an almost full OpenReferenda
+		for _ in 1..T::MaxProposals::get() {
+			open_referendum::<T>(&caller);
+		}
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark { remark: vec![0; b as usize] }.into();
+		let text = vec![1; T::MaxProposalLength::get() as usize];
+		let description = vec![1; 32];
+		QuadraticVoting::<T>::note_description(RawOrigin::Signed(caller.clone()).into(), description.clone())?;
+	}: _(RawOrigin::Signed(caller), text, Some(T::Hashing::hash(&description[..])), Some(call.encode()))
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_open_referenda().len() as u32, T::MaxProposals::get());
+	}
+
+	note_description {
+		let b in 0 .. T::MaxDescriptionLength::get();
+		let caller: T::AccountId = account("caller", 0, SEED);
+		T::Token::make_free_balance_be(&caller, CurrencyAmount::<T>::from(u32::MAX));
+		let description = vec![1; b as usize];
+		let hash = T::Hashing::hash(&description[..]);
+	}: _(RawOrigin::Signed(caller), description)
+	verify {
+		assert!(QuadraticVoting::<T>::get_description(hash).is_some());
+	}
+
+	clear_description {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		T::Token::make_free_balance_be(&caller, CurrencyAmount::<T>::from(u32::MAX));
+		let description = vec![1; T::MaxDescriptionLength::get() as usize];
+		let hash = T::Hashing::hash(&description[..]);
+		QuadraticVoting::<T>::note_description(RawOrigin::Signed(caller.clone()).into(), description)?;
+	}: _(RawOrigin::Signed(caller), hash)
+	verify {
+		assert!(QuadraticVoting::<T>::get_description(hash).is_none());
+	}
+
+	reserve_voting_power {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		T::Token::make_free_balance_be(&caller, CurrencyAmount::<T>::from(u32::MAX));
+	}: _(RawOrigin::Signed(caller.clone()), RESERVE.into())
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_reserve(&caller), RESERVE.into());
+	}
+
+	release_all_voting_power {
+		let caller = voter::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_last_event::<T>(Event::AllVotingPowerReleased(caller).into());
+	}
+
+	// Worst case is appending to an almost full unbonding queue
+	release_voting_power {
+		let caller = voter::<T>("caller", 0);
+		for _ in 1..T::MaxUnbondingChunks::get() {
+			QuadraticVoting::<T>::release_voting_power(RawOrigin::Signed(caller.clone()).into(), 1u32.into())?;
+		}
+	}: _(RawOrigin::Signed(caller.clone()), 1u32.into())
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_unbonding(&caller).len() as u32, T::MaxUnbondingChunks::get());
+	}
+
+	withdraw_unbonded {
+		let caller = voter::<T>("caller", 0);
+		for _ in 0..T::MaxUnbondingChunks::get() {
+			QuadraticVoting::<T>::release_voting_power(RawOrigin::Signed(caller.clone()).into(), 1u32.into())?;
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + T::UnbondingPeriod::get().into());
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(QuadraticVoting::<T>::get_unbonding(&caller).is_empty());
+	}
+
+	vote {
+		let d in 0 .. T::MaxDelegators::get();
+		let caller = voter::<T>("caller", 0);
+		for i in 0..d {
+			let delegator = voter::<T>("delegator", i);
+			QuadraticVoting::<T>::delegate(RawOrigin::Signed(delegator).into(), caller.clone())?;
+		}
+		let index = open_referendum::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), index, true)
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_referendum(index).unwrap().voters, d + 1);
+	}
+
+	// Called directly so it can be measured whatever Config::Mode says. Worst case is replacing a ballot from this round
+	vote_with_credits {
+		let caller = voter::<T>("caller", 0);
+		let index = open_referendum::<T>(&caller);
+		QuadraticVoting::<T>::cast_credit_ballot(caller.clone(), index, true, 1)?;
+	}: {
+		QuadraticVoting::<T>::cast_credit_ballot(caller.clone(), index, false, 1)?;
+	}
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_referendum(index).unwrap().tally, (0, 1));
+	}
+
+	commit_vote {
+		let caller = voter::<T>("caller", 0);
+		let index = secret_referendum::<T>(&caller);
+		let hash = commitment::<T>(&caller, true);
+	}: _(RawOrigin::Signed(caller.clone()), index, hash)
+	verify {
+		assert_last_event::<T>(Event::VoteCommitted(caller, index).into());
+	}
+
+	reveal_vote {
+		let d in 0 .. T::MaxDelegators::get();
+		let caller = voter::<T>("caller", 0);
+		for i in 0..d {
+			let delegator = voter::<T>("delegator", i);
+			QuadraticVoting::<T>::delegate(RawOrigin::Signed(delegator).into(), caller.clone())?;
+		}
+		let index = secret_referendum::<T>(&caller);
+		QuadraticVoting::<T>::commit_vote(RawOrigin::Signed(caller.clone()).into(), index, commitment::<T>(&caller, true))?;
+		start_reveal_phase::<T>();
+	}: _(RawOrigin::Signed(caller), index, true, [0u8; 32])
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_referendum(index).unwrap().voters, d + 1);
+	}
+
+	withdraw_proposal {
+		let v in 0 .. T::MaxVoters::get();
+		let caller = member::<T>("caller", 0);
+		let index = voted_referendum::<T>(&caller, v);
+	}: _(RawOrigin::Signed(caller.clone()), index, v)
+	verify {
+		assert_last_event::<T>(Event::DepositRefunded(index, caller, T::ProposalDeposit::get()).into());
+	}
+
+	veto_proposal {
+		let v in 0 .. T::MaxVoters::get();
+		let caller = member::<T>("caller", 0);
+		let index = voted_referendum::<T>(&caller, v);
+		let origin = T::VetoOrigin::successful_origin();
+	}: _<T::Origin>(origin, index, v)
+	verify {
+		assert_last_event::<T>(Event::DepositSlashed(index, caller, T::ProposalDeposit::get()).into());
+	}
+
+	cancel_enactment {
+		let caller = member::<T>("caller", 0);
+		let index = open_referendum::<T>(&caller);
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark { remark: vec![] }.into();
+		let at = frame_system::Pallet::<T>::block_number() + T::EnactmentPeriod::get().into();
+		Enactments::<T>::insert(at, index, BoundedVec::truncate_from(call.encode()));
+		EnactmentBlock::<T>::insert(index, at);
+		let origin = T::VetoOrigin::successful_origin();
+	}: _<T::Origin>(origin, index)
+	verify {
+		assert_last_event::<T>(Event::EnactmentCancelled(index).into());
+	}
+
+	// Dispatching a queued call, without the weight the call itself reports
+	enact {
+		let b in 0 .. T::MaxProposalLength::get() - 8;
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark { remark: vec![0; b as usize] }.into();
+		let encoded_call = call.encode();
+	}: {
+		QuadraticVoting::<T>::enact(0, &encoded_call);
+	}
+	verify {
+		assert_last_event::<T>(Event::ProposalExecuted { proposal: 0, result: Ok(()) }.into());
+	}
+
+	delegate {
+		let c in 1 .. T::MaxDelegationDepth::get();
+		let caller = member::<T>("caller", 0);
+		// The delegate already delegates down a chain, which has to be checked for loops
+		let delegate = member::<T>("chain", 0);
+		let mut previous = delegate.clone();
+		for i in 1..c {
+			let next = member::<T>("chain", i);
+			QuadraticVoting::<T>::delegate(RawOrigin::Signed(previous).into(), next.clone())?;
+			previous = next;
+		}
+	}: _(RawOrigin::Signed(caller.clone()), delegate.clone())
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_delegate(&caller), Some(delegate));
+	}
+
+	undelegate {
+		let c in 1 .. T::MaxDelegationDepth::get();
+		let caller = member::<T>("caller", 0);
+		// Every account up the delegate's chain has its delegated count updated
+		let delegate = member::<T>("chain", 0);
+		let mut previous = delegate.clone();
+		for i in 1..c {
+			let next = member::<T>("chain", i);
+			QuadraticVoting::<T>::delegate(RawOrigin::Signed(previous).into(), next.clone())?;
+			previous = next;
+		}
+		QuadraticVoting::<T>::delegate(RawOrigin::Signed(caller.clone()).into(), delegate)?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_delegate(&caller), None);
+	}
+
+	// Scanning OpenReferenda when none of them close
+	on_initialize {
+		let r in 0 .. T::MaxProposals::get();
+		let caller = member::<T>("caller", 0);
+		for _ in 0..r {
+			open_referendum::<T>(&caller);
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+	}: {
+		QuadraticVoting::<T>::on_initialize(now);
+	}
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_open_referenda().len() as u32, r);
+	}
+
+	// Closing a single referendum, without the weight of its call
+	finalize_referendum {
+		let v in 0 .. T::MaxVoters::get();
+		let caller = member::<T>("caller", 0);
+		let index = voted_referendum::<T>(&caller, v);
+	}: {
+		QuadraticVoting::<T>::finalize_referendum(index);
+	}
+	verify {
+		assert!(QuadraticVoting::<T>::get_archived_proposal(index).is_some());
+	}
+
+	// Slashing commitments nobody revealed, on top of finalize_referendum
+	penalize_unrevealed {
+		let c in 0 .. T::MaxVoters::get();
+		let caller = member::<T>("caller", 0);
+		let index = secret_referendum::<T>(&caller);
+		for i in 0..c {
+			let who = voter::<T>("voter", i);
+			QuadraticVoting::<T>::commit_vote(RawOrigin::Signed(who.clone()).into(), index, commitment::<T>(&who, true))?;
+		}
+	}: {
+		QuadraticVoting::<T>::finalize_referendum(index);
+	}
+	verify {
+		assert!(QuadraticVoting::<T>::get_archived_proposal(index).is_some());
+	}
+
+	prune_archive {
+		let a in 0 .. MAX_ARCHIVED;
+		let caller = member::<T>("caller", 0);
+		for _ in 0..a {
+			let index = open_referendum::<T>(&caller);
+			QuadraticVoting::<T>::withdraw_proposal(RawOrigin::Signed(caller.clone()).into(), index, 0)?;
+		}
+		let expiry = frame_system::Pallet::<T>::block_number() + T::ArchiveRetention::get().into();
+		frame_system::Pallet::<T>::set_block_number(expiry);
+	}: {
+		QuadraticVoting::<T>::on_initialize(expiry);
+	}
+	verify {
+		assert_eq!(QuadraticVoting::<T>::get_archived_proposal(0), None);
+	}
+
+	impl_benchmark_test_suite!(QuadraticVoting, crate::mock::ExtBuilder::build(), crate::mock::Test);
+}
@@ -0,0 +1,62 @@
+// Curves turning the balance an account has reserved into voting power. The pallet asks its
+// `Config::VotingPowerCurve` for the power of each reserve, and for the most any one account may
+// contribute to a referendum when it is finalized.
+
+use frame_support::{
+	sp_runtime::{traits::IntegerSquareRoot, Perbill},
+	traits::Get,
+};
+use sp_std::marker::PhantomData;
+
+/// Maps reserved balance to voting power
+pub trait VotingPowerCurve {
+	/// Voting power of an account with `reserve` reserved
+	fn power(reserve: u128) -> u128;
+
+	/// Most voting power a single account may add to a referendum with `turnout` voting power cast before any ballot
+	/// is capped, or `None` if ballots are counted in full
+	fn max_power(_turnout: u128) -> Option<u128> {
+		None
+	}
+}
+
+/// Square root of the reserve, i.e. quadratic voting
+pub struct SqrtCurve;
+
+impl VotingPowerCurve for SqrtCurve {
+	fn power(reserve: u128) -> u128 {
+		reserve.integer_sqrt()
+	}
+}
+
+/// One vote per unit reserved, i.e. plain token weighted voting
+pub struct LinearCurve;
+
+impl VotingPowerCurve for LinearCurve {
+	fn power(reserve: u128) -> u128 {
+		reserve
+	}
+}
+
+/// Number of bits in the reserve, i.e. `floor(log2(reserve)) + 1` for any non zero reserve
+pub struct LogCurve;
+
+impl VotingPowerCurve for LogCurve {
+	fn power(reserve: u128) -> u128 {
+		(u128::BITS - reserve.leading_zeros()) as u128
+	}
+}
+
+/// Square root of the reserve, with no account counting for more than `MaxShare` of a referendum's turnout before
+/// capping. Capping shrinks the turnout, so a capped account can end up with more than `MaxShare` of the final tally
+pub struct CappedSqrtCurve<MaxShare>(PhantomData<MaxShare>);
+
+impl<MaxShare: Get<Perbill>> VotingPowerCurve for CappedSqrtCurve<MaxShare> {
+	fn power(reserve: u128) -> u128 {
+		reserve.integer_sqrt()
+	}
+
+	fn max_power(turnout: u128) -> Option<u128> {
+		Some(MaxShare::get() * turnout)
+	}
+}
@@ -2,6 +2,18 @@
 
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
+pub use approval::*;
+pub use curve::*;
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+mod approval;
+mod curve;
+pub mod migrations;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 
 #[cfg(test)]
 mod mock;
@@ -13,112 +25,654 @@ mod tests;
 pub mod pallet {
 use frame_support::pallet_prelude::DispatchResult;
 use frame_support::{pallet_prelude::*, traits::ReservableCurrency, traits::Currency};
-	use frame_support::storage::types::StorageValue;
+	use frame_support::traits::{Imbalance, LockIdentifier, LockableCurrency, OnUnbalanced, WithdrawReasons};
+	use frame_support::storage::{types::StorageValue, with_transaction, TransactionOutcome};
 	use frame_system::{pallet_prelude::*};
-	use frame_support::traits::OriginTrait;
+	use frame_support::weights::{extract_actual_weight, GetDispatchInfo, PostDispatchInfo};
 	use sp_std::vec::Vec;
-	use frame_support::sp_runtime::traits::IntegerSquareRoot;
-	use brads_soft_coupling::{ KittiesInterface, IdentityInterface};
+	use frame_support::sp_runtime::{traits::{CheckedAdd, Dispatchable, Hash, IntegerSquareRoot, Saturating, UniqueSaturatedInto, Zero}, Perbill};
+	use brads_soft_coupling::IdentityInterface;
+	use crate::approval::{ApprovalRule, QuorumRule, TallySummary};
+	use crate::curve::VotingPowerCurve;
+	use crate::weights::WeightInfo;
+	#[cfg(feature = "std")]
+	use serde::{Deserialize, Serialize};
 	pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 	pub type CurrencyAmount<T> = <<T as Config>::Token as Currency<AccountIdOf<T>>>::Balance;
+	pub type NegativeImbalanceOf<T> = <<T as Config>::Token as Currency<AccountIdOf<T>>>::NegativeImbalance;
+	/// Identifies a referendum. Assigned in submission order, starting at 0
+	pub type ProposalIndex = u32;
+
+	/// Id of the balance lock holding each account's voting funds
+	pub const VOTING_LOCK_ID: LockIdentifier = *b"quadvote";
+
+	/// The in-code storage version. Version 1 holds voting funds under `VOTING_LOCK_ID` instead of reserving them,
+	/// version 2 splits proposals out of referenda, version 3 keeps `TotalVotingPower`, version 4 records the reserve
+	/// behind each commitment, version 5 keeps `TotalCreditVotes` and version 6 keeps `DescriptionRefs`
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
+
+	// A single voter's ballot on a proposal, as recorded in the vote ledger
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct VoteRecord<AccountId> {
+		// `true` for aye, `false` for nay
+		pub aye: bool,
+		// The voting power that was added to the tally for this ballot
+		pub power: u128,
+		// The delegate whose vote cast this ballot for the account, or `None` if the account voted itself
+		pub via: Option<AccountId>,
+	}
+
+	// Where a proposal is in its lifecycle
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum ProposalStatus {
+		/// Its referendum is open
+		Voting,
+		Passed,
+		Rejected,
+		Withdrawn,
+		Vetoed,
+		/// Passed, but its call was cancelled before enactment
+		Cancelled,
+	}
+
+	impl From<ProposalOutcome> for ProposalStatus {
+		fn from(outcome: ProposalOutcome) -> Self {
+			match outcome {
+				ProposalOutcome::Passed => ProposalStatus::Passed,
+				ProposalOutcome::Rejected => ProposalStatus::Rejected,
+				ProposalOutcome::Withdrawn => ProposalStatus::Withdrawn,
+				ProposalOutcome::Vetoed => ProposalStatus::Vetoed,
+			}
+		}
+	}
+
+	// A submitted proposal. The title and noted description say what it is, and the optional call is what gets
+	// dispatched if it passes
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ProposalInfo<T: Config> {
+		pub proposer: T::AccountId,
+		pub title: BoundedVec<u8, T::MaxProposalLength>,
+		// Hash of a description noted with `note_description`
+		pub description: Option<T::Hash>,
+		// SCALE encoded `Config::Call`, checked to decode when the proposal is submitted
+		pub call: Option<BoundedVec<u8, T::MaxProposalLength>>,
+		// Block the proposal was submitted in
+		pub submitted: T::BlockNumber,
+		pub status: ProposalStatus,
+	}
+
+	// A noted proposal description and the deposit held for it
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct DescriptionInfo<T: Config> {
+		pub depositor: T::AccountId,
+		pub deposit: CurrencyAmount<T>,
+		pub text: BoundedVec<u8, T::MaxDescriptionLength>,
+	}
+
+	// The voting state of an open proposal, with its own voting window and tally
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ReferendumInfo<T: Config> {
+		// Amount reserved from the proposer until the referendum is closed
+		pub deposit: CurrencyAmount<T>,
+		// Block the referendum was opened for voting
+		pub start: T::BlockNumber,
+		// Block at which voting closes and the referendum is tallied
+		pub end: T::BlockNumber,
+		// Running (ayeVotes, nayVotes)
+		pub tally: (u128, u128),
+		// Number of accounts with a ballot in the vote ledger
+		pub voters: u32,
+		// Whether ballots are committed as hashes first and only counted once revealed
+		pub secret: bool,
+	}
+
+	// Voting funds on their way out, which stay locked until `unlock_at`
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct UnbondingChunk<Balance, BlockNumber> {
+		pub amount: Balance,
+		pub unlock_at: BlockNumber,
+	}
+
+	// A hidden ballot on a secret referendum, waiting to be revealed
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Commitment<Hash, Balance> {
+		// Hash of (voter, verdict, salt)
+		pub hash: Hash,
+		// The voter's power when they committed, which is what gets counted on reveal
+		pub power: u128,
+		// The voter's reserve when they committed, which UnrevealedPenalty is taken of if they never reveal
+		pub reserve: Balance,
+	}
+
+	/// How ballots are weighed
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum VotingMode {
+		/// A ballot counts with the voting power of the voter's reserve along `VotingPowerCurve`, using `vote`
+		SqrtOfReserve,
+		/// Members get a voice credit budget every round and spend it across referenda with `vote_with_credits`.
+		/// Casting k votes on one referendum costs k² credits
+		VoiceCredits,
+	}
+
+	/// Where each member's per round voice credit budget comes from
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum CreditSource {
+		/// Every member gets the same number of credits
+		Flat(u128),
+		/// A member gets as many credits as they hold in voting funds
+		Reserve,
+	}
+
+	// How a referendum was closed
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum ProposalOutcome {
+		Passed,
+		Rejected,
+		Withdrawn,
+		Vetoed,
+	}
+
+	// Whether the call of a passed proposal dispatched successfully, or was cancelled while waiting for enactment
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum ExecutionOutcome {
+		Succeeded,
+		Failed,
+		Cancelled,
+	}
+
+	/// An open referendum as reported by the runtime API
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct ReferendumStatus<AccountId, BlockNumber> {
+		pub index: ProposalIndex,
+		pub proposer: AccountId,
+		pub text: Vec<u8>,
+		pub has_call: bool,
+		pub start: BlockNumber,
+		pub end: BlockNumber,
+		/// Ayes and nays as they would be counted if the referendum were finalized now, after the curve's cap. Strings
+		/// in JSON, which can't hold a u128 as a number
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_as_string"))]
+		pub ayes: u128,
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_as_string"))]
+		pub nays: u128,
+		pub voters: u32,
+		/// Whether the referendum would pass if it were finalized now, under the configured quorum and approval rules
+		pub projected_pass: bool,
+	}
+
+	/// An account's voting power as reported by the runtime API
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, Default, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct VotingPowerInfo {
+		/// Voting power of the account's own reserve along the configured curve. A string in JSON
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_as_string"))]
+		pub own: u128,
+		/// Power of the accounts that would be counted with the account's ballot through delegation. A string in JSON
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_as_string"))]
+		pub delegated: u128,
+	}
+
+	// What is kept of a referendum once it is closed, until ArchiveRetention blocks have passed
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ArchivedProposal<T: Config> {
+		pub proposer: T::AccountId,
+		pub text: BoundedVec<u8, T::MaxProposalLength>,
+		// Hash of the SCALE encoded call, if the proposal had one
+		pub call_hash: Option<T::Hash>,
+		// Final (ayeVotes, nayVotes)
+		pub tally: (u128, u128),
+		// Number of accounts that had a ballot when the referendum was closed
+		pub voters: u32,
+		pub outcome: ProposalOutcome,
+		// `None` unless the proposal passed with a call, and until that call is enacted or cancelled
+		pub execution: Option<ExecutionOutcome>,
+		// Block the referendum was closed in
+		pub finalized_at: T::BlockNumber,
+	}
+
+	impl<T: Config> ArchivedProposal<T> {
+		// Total voting power cast on the referendum
+		pub fn turnout(&self) -> u128 {
+			self.tally.0.saturating_add(self.tally.1)
+		}
+	}
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
-		type Token: ReservableCurrency<Self::AccountId>; //Loose coupling. This is some notion of token that satisfies a trait
-		/// Configurable constant for max number of proposals in queue to be voted on
+		/// Loose coupling. This is some notion of token whose balances can be locked to back voting power
+		type Token: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber> + ReservableCurrency<Self::AccountId>;
+		/// The overarching call type. Call proposals are decoded into this and dispatched when they pass
+		type Call: Parameter + Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo> + GetDispatchInfo + From<frame_system::Call<Self>>;
+		/// The origin passed call proposals are dispatched with
+		type ExecutionOrigin: Get<Self::Origin>;
+		/// Configurable constant for max number of referenda open for voting at once
 		#[pallet::constant]
 		type MaxProposals: Get<u32>;
 		/// Configurable constant for max length of any single proposal in bytes
 		#[pallet::constant]
 		type MaxProposalLength: Get<u32>;
-		/// Configurable constant for number of blocks that elapse between finalization of each current proposals at front of proposal queue
+		/// Configurable constant for length in blocks of each referendum's voting window
 		#[pallet::constant]
 		type BlocksPerVote: Get<u32>;
-		/// Configurable constant for voting power threshold. If not enough voting power votes on the current proposal, it fails regardless
-		/// of yae/nay ratio
-		#[pallet::constant]
-		type ParticipationThreshold: Get<u128>;
+		/// Rule deciding whether the ayes of a closed referendum beat its nays, e.g. `SuperMajority` or `PositiveTurnoutBias`
+		type ApprovalRule: ApprovalRule;
+		/// Rule deciding whether enough voting power or enough voter set members took part. If not, the referendum fails
+		/// regardless of yae/nay ratio
+		type QuorumRule: QuorumRule;
+		/// Curve turning reserved balance into voting power in `SqrtOfReserve` mode, e.g. `SqrtCurve` or
+		/// `CappedSqrtCurve`. Changing it leaves `TotalVotingPower` stale until reserves are touched again
+		type VotingPowerCurve: VotingPowerCurve;
 		/// Soft coupled interface for identity-pallet
 		type Identity: IdentityInterface<Self::Origin, Self::AccountId, DispatchResult>;
-		/// Soft coupled interface for crypto-kitties pallet
-		type Kitties: KittiesInterface<Self::Origin, Self::AccountId, <Self::Token as Currency<Self::AccountId>>::Balance, BoundedVec<[u8;16], ConstU32<1>>,  DispatchResult>;
+		/// Configurable constant for max number of accounts that can delegate to one delegate, directly or through a chain.
+		/// Bounds the delegators a single ballot walks
+		#[pallet::constant]
+		type MaxDelegators: Get<u32>;
+		/// Configurable constant for max length of a delegation chain. Longer chains can't be created, and are never followed further
+		#[pallet::constant]
+		type MaxDelegationDepth: Get<u32>;
+		/// Origin allowed to veto an open referendum, or cancel a passed one before it is enacted
+		type VetoOrigin: EnsureOrigin<Self::Origin>;
+		/// Configurable constant for the number of blocks between a proposal passing and its call being dispatched
+		#[pallet::constant]
+		type EnactmentPeriod: Get<u32>;
+		/// Configurable constant for the amount reserved from a proposer when they submit a proposal
+		#[pallet::constant]
+		type ProposalDeposit: Get<CurrencyAmount<Self>>;
+		/// Configurable constant for max length of a noted proposal description in bytes
+		#[pallet::constant]
+		type MaxDescriptionLength: Get<u32>;
+		/// Configurable constant for the flat part of the deposit reserved for noting a description
+		#[pallet::constant]
+		type DescriptionBaseDeposit: Get<CurrencyAmount<Self>>;
+		/// Configurable constant for the per byte part of the deposit reserved for noting a description
+		#[pallet::constant]
+		type DescriptionByteDeposit: Get<CurrencyAmount<Self>>;
+		/// Configurable constant for the share of the turnout a rejected proposal needs in ayes to have its deposit
+		/// refunded rather than slashed
+		#[pallet::constant]
+		type DepositRefundThreshold: Get<Perbill>;
+		/// Handler for slashed proposal deposits
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// Configurable constant for the number of blocks a closed referendum is kept in the Archive
+		#[pallet::constant]
+		type ArchiveRetention: Get<u32>;
+		/// Whether newly submitted proposals are voted on with secret ballots. A secret referendum spends the first
+		/// half of BlocksPerVote collecting ballot commitments and the second half revealing them
+		#[pallet::constant]
+		type SecretBallots: Get<bool>;
+		/// Configurable constant for the share of the reserve they committed with slashed from voters who commit but never
+		/// reveal. It's taken out of their voting funds only
+		#[pallet::constant]
+		type UnrevealedPenalty: Get<Perbill>;
+		/// Whether ballots are weighed by reserve or paid for with voice credits
+		#[pallet::constant]
+		type Mode: Get<VotingMode>;
+		/// Where voice credit budgets come from, when Mode is VoiceCredits
+		#[pallet::constant]
+		type VoiceCredits: Get<CreditSource>;
+		/// Configurable constant for the length in blocks of a voice credit round. Unspent credits expire when it ends
+		#[pallet::constant]
+		type CreditRoundLength: Get<u32>;
+		/// Configurable constant for the number of blocks released voting funds stay locked after their voting power is
+		/// gone
+		#[pallet::constant]
+		type UnbondingPeriod: Get<u32>;
+		/// Configurable constant for the number of releases an account can have unbonding at once
+		#[pallet::constant]
+		type MaxUnbondingChunks: Get<u32>;
+		/// Configurable constant for max number of accounts with a ballot or an unrevealed commitment on one referendum.
+		/// Bounds the ballots finalizing a referendum walks and clears
+		#[pallet::constant]
+		type MaxVoters: Get<u32>;
+		/// Weight information for extrinsics and hooks in this pallet
+		type WeightInfo: WeightInfo;
+		/// Puts the accounts the benchmarks vote with in the voter set, which `Identity` has no way to do
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: crate::BenchmarkHelper<Self::AccountId>;
 	}
 	// Pallets use events to inform users when important changes are made.
 	// Event documentation should end with an array that provides descriptive names for parameters.
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Proposal submitted [submitter, proposal]
-		ProposalSubmitted(T::AccountId, BoundedVec<u8, T::MaxProposalLength>),
+		/// Proposal submitted and opened for voting [submitter, proposalIndex, proposal, votingEnds]
+		ProposalSubmitted(T::AccountId, ProposalIndex, BoundedVec<u8, T::MaxProposalLength>, T::BlockNumber),
 		/// Event emitted when voting power is reserved by locking currency [who, amount]
 		VotingPowerReserved(T::AccountId, CurrencyAmount<T>),
-		/// Event emitted when voting power is released by unlocking currency [who]
+		/// Event emitted when all of an account's voting power is released [who]
 		AllVotingPowerReleased(T::AccountId),
-		/// Voted on current proposal [user, (ayeVotes, nayVotes)]
-		VotedOnCurrentProposal(T::AccountId, (u128, u128)),
-		/// Vote on current proposal finalized [proposal, (ayeVotes, nayVotes)]
-		VoteOnCurrentProposalPassed(BoundedVec<u8, T::MaxProposalLength>, (u128, u128)),
-		/// Vote on current proposal failed [proposal, (ayeVotes, nayVotes)]
-		CurrentProposalRejected(BoundedVec<u8, T::MaxProposalLength>, (u128, u128)),
+		/// Voting power released. The funds behind it unlock after UnbondingPeriod [who, amount, unlockAt]
+		VotingPowerUnbonding(T::AccountId, CurrencyAmount<T>, T::BlockNumber),
+		/// Unbonded voting funds unlocked [who, amount]
+		UnbondedWithdrawn(T::AccountId, CurrencyAmount<T>),
+		/// Voted on a referendum [user, proposalIndex, (ayeVotes, nayVotes)]
+		Voted(T::AccountId, ProposalIndex, (u128, u128)),
+		/// First ballot of a voter on a referendum recorded in the ledger [voter, proposalIndex, verdict, power]
+		VoteRecorded(T::AccountId, ProposalIndex, bool, u128),
+		/// A voter replaced their earlier ballot on a referendum [voter, proposalIndex, verdict, power]
+		VoteChanged(T::AccountId, ProposalIndex, bool, u128),
+		/// Referendum passed when its voting window closed [proposalIndex, proposal, (ayeVotes, nayVotes)]
+		ProposalPassed(ProposalIndex, BoundedVec<u8, T::MaxProposalLength>, (u128, u128)),
+		/// Referendum failed when its voting window closed [proposalIndex, proposal, (ayeVotes, nayVotes)]
+		ProposalRejected(ProposalIndex, BoundedVec<u8, T::MaxProposalLength>, (u128, u128)),
+		/// The call attached to a passed proposal was queued for enactment [proposalIndex, enactmentBlock]
+		EnactmentScheduled(ProposalIndex, T::BlockNumber),
+		/// The call attached to a passed proposal was dispatched
+		ProposalExecuted { proposal: ProposalIndex, result: DispatchResult },
+		/// The call attached to a passed proposal failed, and its storage changes were rolled back
+		ProposalExecutionFailed { proposal: ProposalIndex, error: DispatchError },
+		/// A passed proposal's call was cancelled before enactment [proposalIndex]
+		EnactmentCancelled(ProposalIndex),
+		/// Voting power delegated [delegator, delegate]
+		Delegated(T::AccountId, T::AccountId),
+		/// Delegation withdrawn by the delegator [delegator]
+		Undelegated(T::AccountId),
+		/// Delegations to and from an account dropped because it left the voter set [who]
+		DelegationsCleared(T::AccountId),
+		/// Open referendum withdrawn by its proposer [index, proposer]
+		ProposalWithdrawn(ProposalIndex, T::AccountId),
+		/// Open referendum vetoed [index]
+		ProposalVetoed(ProposalIndex),
+		/// A proposal description was noted [hash, depositor, deposit]
+		DescriptionNoted(T::Hash, T::AccountId, CurrencyAmount<T>),
+		/// A noted description was removed and its deposit returned [hash, depositor, deposit]
+		DescriptionCleared(T::Hash, T::AccountId, CurrencyAmount<T>),
+		/// Proposal deposit returned to the proposer [index, proposer, amount]
+		DepositRefunded(ProposalIndex, T::AccountId, CurrencyAmount<T>),
+		/// Proposal deposit slashed from the proposer [index, proposer, amount]
+		DepositSlashed(ProposalIndex, T::AccountId, CurrencyAmount<T>),
+		/// Hidden ballot committed on a secret referendum [voter, index]
+		VoteCommitted(T::AccountId, ProposalIndex),
+		/// Voting funds slashed for a commitment that was never revealed [voter, index, amount]
+		UnrevealedVotePenalized(T::AccountId, ProposalIndex, CurrencyAmount<T>),
+		/// Voice credits spent on a referendum [voter, index, votes, creditsLeftThisRound]
+		VoiceCreditsSpent(T::AccountId, ProposalIndex, u32, u128),
 	}
 	// All the errors that can prevent successful execution of this pallet's calls
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Doesn't make sense to add an empty proposal
 		TriedToAddEmptyProposal,
-		/// Too many proposals in the queue. Can't add one.
+		/// Too many referenda open for voting. Can't add one.
 		TooManyProposals,
 		/// The reserve amount is either more than the sender can afford, or 0
 		InvalidReserveAmount,
-		/// The user in question didn't have any voting reserves
+		/// The user in question didn't have any voting funds locked
 		NoVotingPowerToRelease,
+		/// The release amount is either more than the sender's reserve, or 0
+		InvalidReleaseAmount,
+		/// The account has MaxUnbondingChunks releases unbonding already
+		TooManyUnbondingChunks,
+		/// None of the account's unbonding funds have passed UnbondingPeriod yet
+		NothingToWithdraw,
 		/// Tried to vote with no voting power
 		VotedWithNoVotingPower,
 		/// Tried to vote while not in voter set
 		NotInVoterSet,
-		/// Tried to vote but there are no proposals
+		/// The referendum has more voters than the witness given for it
+		VoterWitnessTooLow,
+		/// The referendum already has MaxVoters ballots and commitments
+		TooManyVoters,
+		/// Tried to vote on a referendum which doesn't exist or is already finalized
 		NoProposalToVoteFor,
 		/// Failed converting balance type to vote number
 		BalanceToVoteConvertFailed,
+		/// The encoded call is longer than MaxProposalLength
+		ProposalCallTooLong,
+		/// The proposal call could not be decoded into a runtime call
+		UndecodableCall,
+		/// Can't delegate voting power to yourself
+		DelegatedToSelf,
+		/// The account being delegated to isn't in the voter set
+		DelegateNotInVoterSet,
+		/// Following the delegate's own delegations leads back to the sender
+		DelegationLoop,
+		/// The delegate's delegation chain is already MaxDelegationDepth long
+		DelegationChainTooLong,
+		/// The delegate, or an account further up their chain, would end up with more than MaxDelegators delegators
+		TooManyDelegators,
+		/// Tried to undelegate without having delegated
+		NotDelegating,
+		/// The reserve backs a ballot on a referendum that hasn't been finalized yet
+		VotingPowerLocked,
+		/// Only the account that submitted a proposal can withdraw it
+		NotProposer,
+		/// The proposer can't afford ProposalDeposit
+		InsufficientProposalDeposit,
+		/// Ballots on a secret referendum have to be committed and revealed
+		SecretBallotRequired,
+		/// Commitments are only for secret referenda
+		NotSecretReferendum,
+		/// The referendum's commit phase is over
+		NotInCommitPhase,
+		/// The referendum's reveal phase hasn't started yet
+		NotInRevealPhase,
+		/// There is no commitment from the sender to reveal
+		NoCommitment,
+		/// The revealed verdict and salt don't match the commitment
+		CommitmentMismatch,
+		/// A description with this hash is already noted
+		DescriptionAlreadyNoted,
+		/// No description with this hash has been noted
+		DescriptionNotNoted,
+		/// The description is longer than MaxDescriptionLength
+		DescriptionTooLong,
+		/// The proposal has no call waiting for enactment
+		NotScheduled,
+		/// Only the account that noted a description can clear it
+		NotDescriptionDepositor,
+		/// An open referendum or a queued enactment still refers to the description
+		DescriptionInUse,
+		/// The sender can't afford the description deposit
+		InsufficientDescriptionDeposit,
+		/// The call isn't available in the configured voting mode
+		WrongVotingMode,
+		/// Ballots in voice credit mode need at least one vote
+		NoVotesCast,
+		/// The votes cost more voice credits than are left this round
+		NotEnoughVoiceCredits,
 	}
 
 	// The struct on which all this pallet's logic is implemented
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
-	#[pallet::getter(fn get_proposals)]
-	/// A structure containing a bounded vec of proposal strings as byet vectors in order earliest to latest
-	pub(super) type Proposals<T: Config> = StorageValue<_, BoundedVec<BoundedVec<u8, T::MaxProposalLength>, T::MaxProposals>, ValueQuery>;
+	#[pallet::getter(fn get_referendum_count)]
+	/// Number of referenda ever submitted. Also the index the next referendum will get
+	pub(super) type ReferendumCount<T: Config> = StorageValue<_, ProposalIndex, ValueQuery>;
 
 	#[pallet::storage]
-	#[pallet::getter(fn get_proposers)]
-	/// A storage value containing the bounded vec of all proposers in order earliest to latest
-	pub(super) type Proposers<T: Config> = StorageValue<_, BoundedVec<T::AccountId, T::MaxProposalLength>, ValueQuery>;
+	#[pallet::getter(fn get_proposal)]
+	/// Every proposal, from submission until its archive record is pruned
+	pub(super) type Proposals<T: Config> = StorageMap<_, Twox64Concat, ProposalIndex, ProposalInfo<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_description)]
+	/// Proposal descriptions, keyed by their hash
+	pub(super) type Descriptions<T: Config> = StorageMap<_, Identity, T::Hash, DescriptionInfo<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_description_refs)]
+	/// Number of open referenda and queued enactments whose proposal refers to each description. A description can't
+	/// be cleared while it's referred to
+	pub(super) type DescriptionRefs<T: Config> = StorageMap<_, Identity, T::Hash, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_referendum)]
+	/// Every referendum still open for voting, with its proposal, voting window and tally
+	pub(super) type Referenda<T: Config> = StorageMap<_, Twox64Concat, ProposalIndex, ReferendumInfo<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_open_referenda)]
+	/// Indexes and end blocks of open referenda, so on_initialize can find the ones closing without reading each one
+	pub(super) type OpenReferenda<T: Config> = StorageValue<_, BoundedVec<(ProposalIndex, T::BlockNumber), T::MaxProposals>, ValueQuery>;
 	
 	#[pallet::storage]
 	#[pallet::getter(fn get_reserve)]
-	/// The map containing associated reserves for each account id. Determines voting power.
+	/// The amount each account has locked under `VOTING_LOCK_ID` for voting. Determines voting power.
 	pub(super) type ReserveSet<T: Config> = CountedStorageMap<_, Blake2_128, T::AccountId, CurrencyAmount<T>, ValueQuery>;
 	
 	#[pallet::storage]
-	#[pallet::getter(fn get_tally)]
-	/// The vote tally for the current proposal
-	pub(super) type Tally<T: Config> = StorageValue<_, (u128, u128), ValueQuery>;
+	#[pallet::getter(fn get_total_voting_power)]
+	/// Sum of the voting power of every account in ReserveSet. The electorate quorum and turnout biased approval rules
+	/// see when Mode is SqrtOfReserve
+	pub(super) type TotalVotingPower<T: Config> = StorageValue<_, u128, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_total_credit_votes)]
+	/// Sum of the votes every account in ReserveSet could cast on one referendum with a voice credit budget of its
+	/// reserve. The electorate rules see when Mode is VoiceCredits and credits come from the Reserve
+	pub(super) type TotalCreditVotes<T: Config> = StorageValue<_, u128, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vote)]
+	/// Ledger of ballots cast on each open referendum, keyed by proposal index and voter. Lets a voter change their
+	/// ballot without being counted twice. Cleared when the referendum is finalized.
+	pub(super) type Votes<T: Config> = StorageDoubleMap<_, Twox64Concat, ProposalIndex, Blake2_128Concat, T::AccountId, VoteRecord<T::AccountId>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_archived_proposal)]
+	/// Outcome records of closed referenda
+	pub(super) type Archive<T: Config> = StorageMap<_, Twox64Concat, ProposalIndex, ArchivedProposal<T>, OptionQuery>;
+
+	#[pallet::storage]
+	/// Calls of passed proposals waiting to be dispatched, keyed by the block they are enacted in
+	pub(super) type Enactments<T: Config> = StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Twox64Concat, ProposalIndex, BoundedVec<u8, T::MaxProposalLength>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_enactment_block)]
+	/// The block each pending enactment is scheduled for
+	pub(super) type EnactmentBlock<T: Config> = StorageMap<_, Twox64Concat, ProposalIndex, T::BlockNumber, OptionQuery>;
+
+	#[pallet::storage]
+	/// The archived referenda to prune at each block
+	pub(super) type ArchiveExpiry<T: Config> = StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Twox64Concat, ProposalIndex, (), OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vote_lock)]
+	/// The block until which each account's reserve can't be released, being the end of the last referendum it has a
+	/// ballot on, whether cast by itself or by a delegate
+	pub(super) type VoteLocks<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_unbonding)]
+	/// Released voting funds per account, oldest first. They no longer count for voting power but stay locked until
+	/// withdrawn after UnbondingPeriod
+	pub(super) type Unbonding<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<UnbondingChunk<CurrencyAmount<T>, T::BlockNumber>, T::MaxUnbondingChunks>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_commitment)]
+	/// Unrevealed ballots on secret referenda, keyed by proposal index and voter. Whatever is left when the referendum
+	/// is finalized gets penalized
+	pub(super) type Commitments<T: Config> = StorageDoubleMap<_, Twox64Concat, ProposalIndex, Blake2_128Concat, T::AccountId, Commitment<T::Hash, CurrencyAmount<T>>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_commitment_count)]
+	/// Number of `Commitments` entries on each open referendum. Counts towards MaxVoters along with its voters
+	pub(super) type CommitmentCount<T: Config> = StorageMap<_, Twox64Concat, ProposalIndex, u32, ValueQuery>;
+
+	#[pallet::storage]
+	/// Closed referenda with ballots or commitments left over after clearing MaxVoters of each, which on_idle clears
+	/// the rest of
+	pub(super) type StaleBallots<T: Config> = StorageMap<_, Twox64Concat, ProposalIndex, (), OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_credits_spent)]
+	/// Voice credits each member has spent, and the round they were spent in. Spending from an earlier round no longer counts
+	pub(super) type VoiceCreditsSpent<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, (u32, u128), OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_credit_ballot)]
+	/// Votes bought on each referendum in voice credit mode, with the round they were bought in. Lets a voter change
+	/// their ballot within a round and get the earlier cost back
+	pub(super) type CreditBallots<T: Config> = StorageDoubleMap<_, Twox64Concat, ProposalIndex, Blake2_128Concat, T::AccountId, (u32, u32), OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_delegate)]
+	/// The account each delegator has handed their voting power to
+	pub(super) type Delegations<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_delegators)]
+	/// The accounts delegating directly to each delegate. The reverse of Delegations, used to find delegated power at vote time
+	pub(super) type Delegators<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<T::AccountId, T::MaxDelegators>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_delegated_count)]
+	/// Number of accounts delegating to each delegate, directly or through a chain. Kept within MaxDelegators, so a ballot
+	/// never walks more delegators than that
+	pub(super) type DelegatedCount<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		/// Every `BlocksPerVote` blocks a vote is finalized. The finalization process is triggered by on_initialize
-		fn on_initialize(b: BlockNumberFor<T>) -> u64{
-			// Every few blocks finalize the vote on another proposal
-			if (b % T::BlocksPerVote::get().into()) == BlockNumberFor::<T>::from(0u32) {
-				Self::finalize_current_vote();
+		/// Moves voting funds still held as reserves by older versions of the pallet onto the voting lock, splits
+		/// proposal records out of referenda, sums up the voting power and voice credit votes already reserved, records
+		/// the reserve behind each commitment and counts the references to each description
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v1::migrate::<T>()
+				.saturating_add(migrations::v2::migrate::<T>())
+				.saturating_add(migrations::v3::migrate::<T>())
+				.saturating_add(migrations::v4::migrate::<T>())
+				.saturating_add(migrations::v5::migrate::<T>())
+				.saturating_add(migrations::v6::migrate::<T>())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			migrations::pre_upgrade::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			migrations::post_upgrade::<T>()
+		}
+
+		/// Each referendum is finalized in the on_initialize of the block its voting window closes
+		fn on_initialize(b: BlockNumberFor<T>) -> Weight {
+			// Collect first, since finalizing removes the referendum from OpenReferenda
+			let open = OpenReferenda::<T>::get();
+			let mut weight = T::WeightInfo::on_initialize(open.len() as u32);
+			let closing: Vec<ProposalIndex> = open.iter()
+				.filter(|(_, end)| *end <= b)
+				.map(|(index, _)| *index)
+				.collect();
+			for index in closing {
+				weight = weight.saturating_add(Self::finalize_referendum(index));
+			}
+			// Dispatch the calls due this block, after finalizing so an EnactmentPeriod of 0 enacts straight away
+			for (index, encoded_call) in Enactments::<T>::drain_prefix(b) {
+				weight = weight.saturating_add(Self::enact(index, &encoded_call));
+			}
+			// Drop archive records that have been kept for ArchiveRetention blocks
+			let mut pruned: u32 = 0;
+			for (index, ()) in ArchiveExpiry::<T>::drain_prefix(b) {
+				Archive::<T>::remove(index);
+				// A call still waiting for enactment can't find its proposal's description once it's gone
+				if let Some(proposal) = Proposals::<T>::take(index) {
+					if EnactmentBlock::<T>::contains_key(index) {
+						Self::release_description(&proposal);
+					}
+				}
+				pruned += 1;
 			}
-			10_000
+			weight.saturating_add(T::WeightInfo::prune_archive(pruned))
+		}
+
+		/// Ballots of closed referenda that didn't all fit in the block they closed in are cleared with leftover weight
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::clear_stale_ballots(remaining_weight)
 		}
 	}
 
@@ -126,160 +680,1196 @@ use frame_support::{pallet_prelude::*, traits::ReservableCurrency, traits::Curre
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 
-		/// Adds a new proposal to the end of the queue.
+		/// Submits a new proposal and opens a referendum on it for the next `BlocksPerVote` blocks. `description` is
+		/// the hash of a description noted with `note_description`. `call` is an optional SCALE encoded runtime call
+		/// which is dispatched with `ExecutionOrigin` if the proposal passes.
+		/// `ProposalDeposit` is reserved from the sender, and returned if the proposal passes, is withdrawn, or gets at
+		/// least `DepositRefundThreshold` of the turnout in ayes. Otherwise it is slashed.
 		/// Events: ProposalSubmitted
-		/// Errors: TriedToAddEmptyProposal, TooManyProposals
-		#[pallet::weight(1_000)]
-		pub fn add_proposal(origin: OriginFor<T>, proposal_string: Vec<u8>) -> DispatchResult {
+		/// Errors: TriedToAddEmptyProposal, DescriptionNotNoted, TooManyProposals, ProposalCallTooLong, UndecodableCall,
+		/// InsufficientProposalDeposit
+		#[pallet::weight(T::WeightInfo::add_proposal(call.as_ref().map_or(0, |call| call.len() as u32)))]
+		pub fn add_proposal(origin: OriginFor<T>, title: Vec<u8>, description: Option<T::Hash>, call: Option<Vec<u8>>) -> DispatchResult {
 			let sender = ensure_signed(origin.clone())?;
-			Self::add_proposal_impl(sender, proposal_string)?;
+			Self::add_proposal_impl(sender, title, description, call)?;
 			Ok(())
 		}
 
-		/// Reserves voting power for a particular account id by reserving tokens
+		/// Stores a proposal description on chain so proposals can refer to it by hash. A deposit of
+		/// `DescriptionBaseDeposit` plus `DescriptionByteDeposit` per byte is reserved until it is cleared.
+		/// Events: DescriptionNoted
+		/// Errors: DescriptionTooLong, DescriptionAlreadyNoted, InsufficientDescriptionDeposit
+		#[pallet::weight(T::WeightInfo::note_description(description.len() as u32))]
+		pub fn note_description(origin: OriginFor<T>, description: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::note_description_impl(sender, description)?;
+			Ok(())
+		}
+
+		/// Removes a description the sender noted and returns its deposit, once no open referendum or queued enactment
+		/// refers to it. Closed proposals keep the hash.
+		/// Events: DescriptionCleared
+		/// Errors: DescriptionNotNoted, NotDescriptionDepositor, DescriptionInUse
+		#[pallet::weight(T::WeightInfo::clear_description())]
+		pub fn clear_description(origin: OriginFor<T>, hash: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::clear_description_impl(sender, hash)?;
+			Ok(())
+		}
+
+		/// Reserves voting power for a particular account id by locking tokens. Locked tokens stay in the free balance
+		/// and can still pay transaction fees, but can't be transferred.
 		/// Events: VotingPowerReserved
 		/// Errors: InvalidReserveAmount
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::reserve_voting_power())]
 		pub fn reserve_voting_power(origin: OriginFor<T>, amount: CurrencyAmount<T>) -> DispatchResult {
 			let sender = ensure_signed(origin.clone())?;
 			Self::reserve_voting_power_impl(sender, amount)?;
 			Ok(())
 		}
 
-		/// Releases all voting power for a particular account, also unreserving their tokens
-		/// Events: AllVotingPowerReleased
-		/// Errors: NoVotingPowerToRelease
-		#[pallet::weight(1_000)]
+		/// Releases all voting power for a particular account. The voting power is gone at once, but the tokens stay
+		/// locked for UnbondingPeriod blocks and are then unlocked with `withdraw_unbonded`
+		/// Events: VotingPowerUnbonding, AllVotingPowerReleased
+		/// Errors: NoVotingPowerToRelease, VotingPowerLocked, TooManyUnbondingChunks
+		#[pallet::weight(T::WeightInfo::release_all_voting_power())]
 		pub fn release_all_voting_power(origin: OriginFor<T>) -> DispatchResult {
 			let sender = ensure_signed(origin.clone())?;
 			Self::release_all_voting_power_impl(sender)?;
 			Ok(())
 		}
 
-		/// Votes aye or nay on current proposal, adding the square root of your reserve to either voting side
-		/// Events: VotedOnCurrentProposal
-		/// Errors: VotedWithNoVotingPower, NotInVoterSet, NoProposalToVoteFor
-		#[pallet::weight(1_000)]
-		pub fn vote_on_current_proposal(origin: OriginFor<T>, verdict: bool) -> DispatchResult {
+		/// Releases part of an account's voting power. Like `release_all_voting_power`, the tokens go through
+		/// UnbondingPeriod before they can be withdrawn
+		/// Events: VotingPowerUnbonding
+		/// Errors: NoVotingPowerToRelease, InvalidReleaseAmount, VotingPowerLocked, TooManyUnbondingChunks
+		#[pallet::weight(T::WeightInfo::release_voting_power())]
+		pub fn release_voting_power(origin: OriginFor<T>, amount: CurrencyAmount<T>) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::release_voting_power_impl(sender, amount)?;
+			Ok(())
+		}
+
+		/// Unlocks the sender's released voting funds that have been unbonding for UnbondingPeriod blocks
+		/// Events: UnbondedWithdrawn
+		/// Errors: NothingToWithdraw
+		#[pallet::weight(T::WeightInfo::withdraw_unbonded())]
+		pub fn withdraw_unbonded(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::withdraw_unbonded_impl(sender)?;
+			Ok(())
+		}
+
+		/// Votes aye or nay on an open referendum, adding the voting power of your reserve to either voting side.
+		/// Voting again replaces the earlier ballot instead of adding to it. Accounts delegating to the sender, directly
+		/// or through a chain, are counted on the same side unless they cast their own ballot on this referendum.
+		/// The reserve backing the ballot, and the reserves of delegators counted with it, stay locked until the
+		/// referendum is finalized.
+		/// Events: VoteRecorded or VoteChanged, Voted
+		/// Errors: WrongVotingMode, VotedWithNoVotingPower, NotInVoterSet, NoProposalToVoteFor, SecretBallotRequired
+		// The delegators walked are everyone delegating to the sender, of which there are at most MaxDelegators. The
+		// number actually walked is refunded
+		#[pallet::weight(T::WeightInfo::vote(T::MaxDelegators::get()))]
+		pub fn vote(origin: OriginFor<T>, proposal_index: ProposalIndex, verdict: bool) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin.clone())?;
+			let delegators = Self::vote_impl(sender, proposal_index, verdict)?;
+			Ok(Some(T::WeightInfo::vote(delegators)).into())
+		}
+
+		/// Casts `votes` votes aye or nay on an open referendum in voice credit mode, paying votes² voice credits from
+		/// the sender's budget for the current round. Voting again on the same referendum replaces the ballot, and
+		/// returns what the earlier one cost if it was bought this round. Delegations aren't followed in this mode.
+		/// Events: VoiceCreditsSpent, VoteRecorded or VoteChanged, Voted
+		/// Errors: WrongVotingMode, NoVotesCast, NotInVoterSet, NoProposalToVoteFor, SecretBallotRequired, NotEnoughVoiceCredits
+		#[pallet::weight(T::WeightInfo::vote_with_credits())]
+		pub fn vote_with_credits(origin: OriginFor<T>, proposal_index: ProposalIndex, verdict: bool, votes: u32) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::vote_with_credits_impl(sender, proposal_index, verdict, votes)?;
+			Ok(())
+		}
+
+		/// Commits a hidden ballot on a secret referendum during its commit phase. `commitment` is the hash of the
+		/// SCALE encoded `(sender, verdict, salt)`. Committing again replaces the earlier commitment. The sender's power
+		/// is fixed when they commit, and their reserve stays locked until the referendum is finalized.
+		/// Events: VoteCommitted
+		/// Errors: WrongVotingMode, VotedWithNoVotingPower, NotInVoterSet, NoProposalToVoteFor, NotSecretReferendum, NotInCommitPhase
+		#[pallet::weight(T::WeightInfo::commit_vote())]
+		pub fn commit_vote(origin: OriginFor<T>, proposal_index: ProposalIndex, commitment: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::commit_vote_impl(sender, proposal_index, commitment)?;
+			Ok(())
+		}
+
+		/// Reveals a committed ballot during the reveal phase of a secret referendum, counting it and the ballots of
+		/// the sender's delegators like `vote` does.
+		/// Events: VoteRecorded or VoteChanged, Voted
+		/// Errors: NoProposalToVoteFor, NotInRevealPhase, NoCommitment, CommitmentMismatch
+		#[pallet::weight(T::WeightInfo::reveal_vote(T::MaxDelegators::get()))]
+		pub fn reveal_vote(origin: OriginFor<T>, proposal_index: ProposalIndex, verdict: bool, salt: [u8; 32]) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin.clone())?;
-			Self::vote_on_current_proposal_impl(sender, verdict)?;
+			let delegators = Self::reveal_vote_impl(sender, proposal_index, verdict, salt)?;
+			Ok(Some(T::WeightInfo::reveal_vote(delegators)).into())
+		}
+
+		/// Withdraws an open referendum before its voting window ends. Only the proposer can withdraw.
+		/// Its ballots are dropped, but the reserves behind them stay locked until the window would have ended.
+		/// The proposal deposit is returned. `voters` has to be at least the referendum's number of voters and
+		/// unrevealed commitments, and is what the weight is charged for up front.
+		/// Events: ProposalWithdrawn, DepositRefunded
+		/// Errors: NoProposalToVoteFor, NotProposer, VoterWitnessTooLow
+		#[pallet::weight(T::WeightInfo::withdraw_proposal(*voters))]
+		pub fn withdraw_proposal(origin: OriginFor<T>, proposal_index: ProposalIndex, voters: u32) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin.clone())?;
+			let voters = Self::withdraw_proposal_impl(sender, proposal_index, voters)?;
+			Ok(Some(T::WeightInfo::withdraw_proposal(voters)).into())
+		}
+
+		/// Cancels the call of a passed proposal that is waiting for enactment. Must be called by VetoOrigin.
+		/// Events: EnactmentCancelled
+		/// Errors: NotScheduled
+		#[pallet::weight(T::WeightInfo::cancel_enactment())]
+		pub fn cancel_enactment(origin: OriginFor<T>, proposal_index: ProposalIndex) -> DispatchResult {
+			T::VetoOrigin::ensure_origin(origin)?;
+			let at = EnactmentBlock::<T>::take(proposal_index).ok_or(Error::<T>::NotScheduled)?;
+			Enactments::<T>::remove(at, proposal_index);
+			Self::note_execution(proposal_index, ExecutionOutcome::Cancelled);
+			Proposals::<T>::mutate(proposal_index, |proposal| {
+				if let Some(proposal) = proposal {
+					proposal.status = ProposalStatus::Cancelled;
+					Self::release_description(proposal);
+				}
+			});
+			// Send success event
+			Self::deposit_event(Event::EnactmentCancelled(proposal_index));
+			Ok(())
+		}
+
+		/// Removes an open referendum without dispatching its call, slashing the proposal deposit. Must be called by VetoOrigin.
+		/// `voters` has to be at least the referendum's number of voters and unrevealed commitments, and is what the
+		/// weight is charged for up front.
+		/// Events: ProposalVetoed, DepositSlashed
+		/// Errors: NoProposalToVoteFor, VoterWitnessTooLow
+		#[pallet::weight(T::WeightInfo::veto_proposal(*voters))]
+		pub fn veto_proposal(origin: OriginFor<T>, proposal_index: ProposalIndex, voters: u32) -> DispatchResultWithPostInfo {
+			T::VetoOrigin::ensure_origin(origin)?;
+			let referendum = Referenda::<T>::get(proposal_index).ok_or(Error::<T>::NoProposalToVoteFor)?;
+			let ballots = referendum.voters.saturating_add(CommitmentCount::<T>::get(proposal_index));
+			ensure!(ballots <= voters, Error::<T>::VoterWitnessTooLow);
+			Self::take_referendum(proposal_index);
+			Self::clear_commitments(proposal_index);
+			Self::archive(proposal_index, &referendum, ProposalOutcome::Vetoed, None);
+			// Send success event
+			Self::deposit_event(Event::ProposalVetoed(proposal_index));
+			Self::slash_deposit(proposal_index, &referendum);
+			Ok(Some(T::WeightInfo::veto_proposal(ballots)).into())
+		}
+
+		/// Hands the sender's voting power to `to`, who must also be in the voter set. The sender can still vote
+		/// on any referendum themselves, which overrides the delegate for that referendum.
+		/// Events: Delegated
+		/// Errors: NotInVoterSet, DelegateNotInVoterSet, DelegatedToSelf, DelegationLoop, DelegationChainTooLong, TooManyDelegators
+		#[pallet::weight(T::WeightInfo::delegate(T::MaxDelegationDepth::get()))]
+		pub fn delegate(origin: OriginFor<T>, to: T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::delegate_impl(sender, to)?;
+			Ok(())
+		}
+
+		/// Stops delegating the sender's voting power
+		/// Events: Undelegated
+		/// Errors: NotDelegating
+		#[pallet::weight(T::WeightInfo::undelegate(T::MaxDelegationDepth::get()))]
+		pub fn undelegate(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin.clone())?;
+			Self::undelegate_impl(sender)?;
 			Ok(())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
-		fn add_proposal_impl(sender: T::AccountId, proposal_string: Vec<u8>) -> Result<(), DispatchError> {
+		fn add_proposal_impl(sender: T::AccountId, title: Vec<u8>, description: Option<T::Hash>, call: Option<Vec<u8>>) -> Result<(), DispatchError> {
 			// Check that proposal is non-empty and that proposer is in voter set
-			ensure!(proposal_string.len() != 0, Error::<T>::TriedToAddEmptyProposal);
+			ensure!(title.len() != 0, Error::<T>::TriedToAddEmptyProposal);
 			ensure!(T::Identity::get_voter_from_set(sender.clone()) == Some(()), Error::<T>::NotInVoterSet);
-			// Add to proposals and proposers BoundedVecs
-			let proposal_as_bounded: BoundedVec<u8, T::MaxProposalLength> = BoundedVec::truncate_from(proposal_string);
-			Proposals::<T>::try_append(proposal_as_bounded.clone())
-				.map_err(|()| Error::<T>::TooManyProposals)?;
-			Proposers::<T>::try_append(sender.clone())
+			if let Some(hash) = description {
+				ensure!(Descriptions::<T>::contains_key(hash), Error::<T>::DescriptionNotNoted);
+			}
+			// A call can't be truncated like the text, so it has to fit and decode as is
+			let call = match call {
+				Some(encoded_call) => {
+					<T as Config>::Call::decode(&mut &encoded_call[..]).map_err(|_| Error::<T>::UndecodableCall)?;
+					Some(BoundedVec::try_from(encoded_call).map_err(|_| Error::<T>::ProposalCallTooLong)?)
+				},
+				None => None,
+			};
+			let deposit = T::ProposalDeposit::get();
+			ensure!(T::Token::can_reserve(&sender, deposit), Error::<T>::InsufficientProposalDeposit);
+			// Open a referendum with its own voting window
+			let index = ReferendumCount::<T>::get();
+			let start = frame_system::Pallet::<T>::block_number();
+			let end = start + T::BlocksPerVote::get().into();
+			OpenReferenda::<T>::try_append((index, end))
 				.map_err(|()| Error::<T>::TooManyProposals)?;
+			T::Token::reserve(&sender, deposit)?;
+			let title: BoundedVec<u8, T::MaxProposalLength> = BoundedVec::truncate_from(title);
+			Proposals::<T>::insert(index, ProposalInfo {
+				proposer: sender.clone(),
+				title: title.clone(),
+				description,
+				call,
+				submitted: start,
+				status: ProposalStatus::Voting,
+			});
+			Referenda::<T>::insert(index, ReferendumInfo {
+				deposit,
+				start,
+				end,
+				tally: (0, 0),
+				voters: 0,
+				secret: T::SecretBallots::get(),
+			});
+			ReferendumCount::<T>::put(index + 1);
+			if let Some(hash) = description {
+				DescriptionRefs::<T>::mutate(hash, |refs| *refs = refs.saturating_add(1));
+			}
 			// Send success event
-			Self::deposit_event(Event::ProposalSubmitted(sender, proposal_as_bounded));
+			Self::deposit_event(Event::ProposalSubmitted(sender, index, title, end));
 			Ok(())
 		}
 
+		fn note_description_impl(sender: T::AccountId, description: Vec<u8>) -> Result<(), DispatchError> {
+			let hash = T::Hashing::hash(&description[..]);
+			ensure!(!Descriptions::<T>::contains_key(hash), Error::<T>::DescriptionAlreadyNoted);
+			let deposit = T::DescriptionByteDeposit::get()
+				.saturating_mul((description.len() as u32).into())
+				.saturating_add(T::DescriptionBaseDeposit::get());
+			let text: BoundedVec<u8, T::MaxDescriptionLength> = description.try_into().map_err(|_| Error::<T>::DescriptionTooLong)?;
+			T::Token::reserve(&sender, deposit).map_err(|_| Error::<T>::InsufficientDescriptionDeposit)?;
+			Descriptions::<T>::insert(hash, DescriptionInfo { depositor: sender.clone(), deposit, text });
+			Self::deposit_event(Event::DescriptionNoted(hash, sender, deposit));
+			Ok(())
+		}
+
+		fn clear_description_impl(sender: T::AccountId, hash: T::Hash) -> Result<(), DispatchError> {
+			let description = Descriptions::<T>::get(hash).ok_or(Error::<T>::DescriptionNotNoted)?;
+			ensure!(description.depositor == sender, Error::<T>::NotDescriptionDepositor);
+			ensure!(DescriptionRefs::<T>::get(hash) == 0, Error::<T>::DescriptionInUse);
+			T::Token::unreserve(&sender, description.deposit);
+			Descriptions::<T>::remove(hash);
+			Self::deposit_event(Event::DescriptionCleared(hash, sender, description.deposit));
+			Ok(())
+		}
+
+		// Returns the number of ballots and commitments dropped, for weight accounting
+		fn withdraw_proposal_impl(sender: T::AccountId, proposal_index: ProposalIndex, voters: u32) -> Result<u32, DispatchError> {
+			let referendum = Referenda::<T>::get(proposal_index).ok_or(Error::<T>::NoProposalToVoteFor)?;
+			let proposer = Proposals::<T>::get(proposal_index).map(|proposal| proposal.proposer);
+			ensure!(proposer == Some(sender.clone()), Error::<T>::NotProposer);
+			let ballots = referendum.voters.saturating_add(CommitmentCount::<T>::get(proposal_index));
+			ensure!(ballots <= voters, Error::<T>::VoterWitnessTooLow);
+			Self::take_referendum(proposal_index);
+			Self::clear_commitments(proposal_index);
+			Self::archive(proposal_index, &referendum, ProposalOutcome::Withdrawn, None);
+			// Send success event
+			Self::deposit_event(Event::ProposalWithdrawn(proposal_index, sender));
+			Self::refund_deposit(proposal_index, &referendum);
+			Ok(ballots)
+		}
+
 		fn reserve_voting_power_impl(sender: T::AccountId, amount: CurrencyAmount<T>) -> Result<(), DispatchError> {
 			// Check for valid reserve amount. 0 is not valid
 			let zero_as_balance: CurrencyAmount<T> = CurrencyAmount::<T>::from(0u32); // Must be a better way to do this, but I don't have time
 			ensure!(amount > zero_as_balance, Error::<T>::InvalidReserveAmount); 
-			// Reserve tokens and add to or create new reserve set entry
-			T::Token::reserve(&sender, amount)?;
-			if ReserveSet::<T>::contains_key(sender.clone()) {
-				let current = ReserveSet::<T>::get(sender.clone());
-				ReserveSet::<T>::insert(sender.clone(), amount + current);
-			}
-			else {
-				ReserveSet::<T>::insert(sender.clone(), amount);
-			}
+			// Lock tokens and add to or create new reserve set entry. The lock covers the whole entry, and overlaps rather
+			// than adds to locks from other pallets, so the free balance has to cover it on its own
+			let current = ReserveSet::<T>::get(sender.clone());
+			let new_total = current.checked_add(&amount).ok_or(Error::<T>::InvalidReserveAmount)?;
+			let locked = new_total.checked_add(&Self::unbonding_total(&sender)).ok_or(Error::<T>::InvalidReserveAmount)?;
+			ensure!(T::Token::free_balance(&sender) >= locked, Error::<T>::InvalidReserveAmount);
+			let old_power = Self::calc_voter_power_from_reserve(current)?;
+			let new_power = Self::calc_voter_power_from_reserve(new_total)?;
+			ReserveSet::<T>::insert(sender.clone(), new_total);
+			Self::update_voting_lock(&sender);
+			Self::update_electorate(current, new_total, old_power, new_power);
 			// Send success event
 			Self::deposit_event(Event::VotingPowerReserved(sender.clone(), amount));
 			Ok(())
 		}
 
 		fn release_all_voting_power_impl(sender: T::AccountId) -> Result<(), DispatchError> {
-			// Make sure there is voting power to release
 			ensure!(ReserveSet::<T>::contains_key(sender.clone()), Error::<T>::NoVotingPowerToRelease);
-			// Remove sender from ReserveSet and unreserve their tokens
 			let amount = ReserveSet::<T>::get(sender.clone());
-			T::Token::unreserve(&sender, amount);
-			ReserveSet::<T>::remove(sender.clone());
+			Self::unbond(&sender, amount)?;
 			// Send success event
 			Self::deposit_event(Event::AllVotingPowerReleased(sender.clone()));
 			Ok(())
 		}
 
-		fn vote_on_current_proposal_impl(sender: T::AccountId, verdict: bool) -> Result<(), DispatchError> {
+		fn release_voting_power_impl(sender: T::AccountId, amount: CurrencyAmount<T>) -> Result<(), DispatchError> {
+			ensure!(ReserveSet::<T>::contains_key(sender.clone()), Error::<T>::NoVotingPowerToRelease);
+			ensure!(!amount.is_zero() && amount <= ReserveSet::<T>::get(sender.clone()), Error::<T>::InvalidReleaseAmount);
+			Self::unbond(&sender, amount)
+		}
+
+		// Takes `amount` out of the sender's reserve and voting power, and queues it for unlocking after UnbondingPeriod
+		fn unbond(sender: &T::AccountId, amount: CurrencyAmount<T>) -> Result<(), DispatchError> {
+			// Make sure the reserve doesn't back a ballot on an open referendum
+			let now = frame_system::Pallet::<T>::block_number();
+			if let Some(locked_until) = VoteLocks::<T>::get(sender) {
+				ensure!(now >= locked_until, Error::<T>::VotingPowerLocked);
+				VoteLocks::<T>::remove(sender);
+			}
+			let unlock_at = now + T::UnbondingPeriod::get().into();
+			Unbonding::<T>::try_append(sender, UnbondingChunk { amount, unlock_at })
+				.map_err(|_| Error::<T>::TooManyUnbondingChunks)?;
+			// Voting power drops straight away
+			let reserve = ReserveSet::<T>::get(sender.clone());
+			let remaining = reserve.saturating_sub(amount);
+			let old_power = Self::calc_voter_power_from_reserve(reserve)?;
+			let new_power = Self::calc_voter_power_from_reserve(remaining)?;
+			if remaining.is_zero() {
+				ReserveSet::<T>::remove(sender.clone());
+			} else {
+				ReserveSet::<T>::insert(sender.clone(), remaining);
+			}
+			Self::update_electorate(reserve, remaining, old_power, new_power);
+			Self::deposit_event(Event::VotingPowerUnbonding(sender.clone(), amount, unlock_at));
+			Ok(())
+		}
+
+		fn withdraw_unbonded_impl(sender: T::AccountId) -> Result<(), DispatchError> {
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut chunks = Unbonding::<T>::get(&sender);
+			let mut withdrawn: CurrencyAmount<T> = Zero::zero();
+			chunks.retain(|chunk| {
+				if chunk.unlock_at <= now {
+					withdrawn = withdrawn.saturating_add(chunk.amount);
+					false
+				} else {
+					true
+				}
+			});
+			ensure!(!withdrawn.is_zero(), Error::<T>::NothingToWithdraw);
+			if chunks.is_empty() {
+				Unbonding::<T>::remove(&sender);
+			} else {
+				Unbonding::<T>::insert(&sender, chunks);
+			}
+			Self::update_voting_lock(&sender);
+			Self::deposit_event(Event::UnbondedWithdrawn(sender, withdrawn));
+			Ok(())
+		}
+
+		fn unbonding_total(who: &T::AccountId) -> CurrencyAmount<T> {
+			Unbonding::<T>::get(who).iter().fold(Zero::zero(), |total: CurrencyAmount<T>, chunk| total.saturating_add(chunk.amount))
+		}
+
+		// Sets our lock to cover the reserve plus everything still unbonding. Only our own lock is touched, so reserves
+		// and locks from other pallets stay as they are
+		fn update_voting_lock(who: &T::AccountId) {
+			let locked = ReserveSet::<T>::get(who.clone()).saturating_add(Self::unbonding_total(who));
+			if locked.is_zero() {
+				T::Token::remove_lock(VOTING_LOCK_ID, who);
+			} else {
+				T::Token::set_lock(VOTING_LOCK_ID, who, locked, WithdrawReasons::TRANSFER);
+			}
+		}
+
+		// Returns the number of delegators walked, for weight accounting
+		fn vote_impl(sender: T::AccountId, proposal_index: ProposalIndex, verdict: bool) -> Result<u32, DispatchError> {
 			// Check failure conditions
+			ensure!(T::Mode::get() == VotingMode::SqrtOfReserve, Error::<T>::WrongVotingMode);
 			ensure!(ReserveSet::<T>::contains_key(sender.clone()), Error::<T>::VotedWithNoVotingPower);
 			if let None = T::Identity::get_voter_from_set(sender.clone()) {
 				return Result::Err(frame_support::dispatch::DispatchError::from(Error::<T>::NotInVoterSet));
 			}
-			ensure!(Proposals::<T>::get().len() > 0, Error::<T>::NoProposalToVoteFor);
-			// Calculate voter power from reserve and add that power to the tally of whichever side they supported
+			let referendum = Referenda::<T>::get(proposal_index).ok_or(Error::<T>::NoProposalToVoteFor)?;
+			ensure!(!referendum.secret, Error::<T>::SecretBallotRequired);
+			// Calculate voter power from reserve
 			let voter_reserve = ReserveSet::<T>::get(sender.clone());
 			let voter_power: u128 = Self::calc_voter_power_from_reserve(voter_reserve)?;
-			let mut current_tally = Tally::<T>::get();
-			if verdict == true {
-				current_tally.0 += voter_power;
+			Self::cast_ballot(sender, proposal_index, referendum, verdict, voter_power, true)
+		}
+
+		fn vote_with_credits_impl(sender: T::AccountId, proposal_index: ProposalIndex, verdict: bool, votes: u32) -> Result<(), DispatchError> {
+			// Check failure conditions
+			ensure!(T::Mode::get() == VotingMode::VoiceCredits, Error::<T>::WrongVotingMode);
+			Self::cast_credit_ballot(sender, proposal_index, verdict, votes)
+		}
+
+		// Spends voice credits on a ballot, whatever the configured mode
+		pub(crate) fn cast_credit_ballot(sender: T::AccountId, proposal_index: ProposalIndex, verdict: bool, votes: u32) -> Result<(), DispatchError> {
+			ensure!(votes > 0, Error::<T>::NoVotesCast);
+			ensure!(T::Identity::get_voter_from_set(sender.clone()).is_some(), Error::<T>::NotInVoterSet);
+			let referendum = Referenda::<T>::get(proposal_index).ok_or(Error::<T>::NoProposalToVoteFor)?;
+			ensure!(!referendum.secret, Error::<T>::SecretBallotRequired);
+			ensure!(
+				Votes::<T>::contains_key(proposal_index, &sender) || Self::has_room(proposal_index, &referendum),
+				Error::<T>::TooManyVoters
+			);
+			// Work out what is left of this round's budget, giving back the cost of an earlier ballot from this round
+			let round = Self::current_round();
+			let mut spent = match VoiceCreditsSpent::<T>::get(&sender) {
+				Some((spent_round, spent)) if spent_round == round => spent,
+				_ => 0,
+			};
+			if let Some((ballot_round, old_votes)) = CreditBallots::<T>::get(proposal_index, &sender) {
+				if ballot_round == round {
+					spent = spent.saturating_sub(Self::credit_cost(old_votes));
+				}
+			}
+			spent = spent.saturating_add(Self::credit_cost(votes));
+			let budget = Self::credit_budget(&sender)?;
+			ensure!(spent <= budget, Error::<T>::NotEnoughVoiceCredits);
+			VoiceCreditsSpent::<T>::insert(&sender, (round, spent));
+			CreditBallots::<T>::insert(proposal_index, &sender, (round, votes));
+			Self::deposit_event(Event::VoiceCreditsSpent(sender.clone(), proposal_index, votes, budget - spent));
+			// Each vote adds one to the tally
+			Self::cast_ballot(sender, proposal_index, referendum, verdict, votes.into(), false)?;
+			Ok(())
+		}
+
+		// Voice credit rounds are numbered from genesis
+		fn current_round() -> u32 {
+			let now = frame_system::Pallet::<T>::block_number();
+			let round_length: T::BlockNumber = T::CreditRoundLength::get().max(1).into();
+			(now / round_length).unique_saturated_into()
+		}
+
+		// k votes cost k² credits
+		fn credit_cost(votes: u32) -> u128 {
+			(votes as u128).saturating_mul(votes as u128)
+		}
+
+		fn credit_budget(who: &T::AccountId) -> Result<u128, Error::<T>> {
+			match T::VoiceCredits::get() {
+				CreditSource::Flat(credits) => Ok(credits),
+				CreditSource::Reserve => ReserveSet::<T>::get(who.clone()).try_into()
+					.map_err(|_err| Error::<T>::BalanceToVoteConvertFailed),
+			}
+		}
+
+		fn commit_vote_impl(sender: T::AccountId, proposal_index: ProposalIndex, commitment: T::Hash) -> Result<(), DispatchError> {
+			// Check failure conditions
+			ensure!(T::Mode::get() == VotingMode::SqrtOfReserve, Error::<T>::WrongVotingMode);
+			ensure!(ReserveSet::<T>::contains_key(sender.clone()), Error::<T>::VotedWithNoVotingPower);
+			ensure!(T::Identity::get_voter_from_set(sender.clone()).is_some(), Error::<T>::NotInVoterSet);
+			let referendum = Referenda::<T>::get(proposal_index).ok_or(Error::<T>::NoProposalToVoteFor)?;
+			ensure!(referendum.secret, Error::<T>::NotSecretReferendum);
+			ensure!(frame_system::Pallet::<T>::block_number() < Self::reveal_start(&referendum), Error::<T>::NotInCommitPhase);
+			// Fix the power now, so changing the reserve before revealing makes no difference
+			let reserve = ReserveSet::<T>::get(sender.clone());
+			let power = Self::calc_voter_power_from_reserve(reserve)?;
+			if !Commitments::<T>::contains_key(proposal_index, &sender) {
+				ensure!(Self::has_room(proposal_index, &referendum), Error::<T>::TooManyVoters);
+				CommitmentCount::<T>::mutate(proposal_index, |count| *count = count.saturating_add(1));
+			}
+			Commitments::<T>::insert(proposal_index, &sender, Commitment { hash: commitment, power, reserve });
+			Self::extend_vote_lock(&sender, referendum.end);
+			// Send success event
+			Self::deposit_event(Event::VoteCommitted(sender, proposal_index));
+			Ok(())
+		}
+
+		// Returns the number of delegators walked, for weight accounting
+		fn reveal_vote_impl(sender: T::AccountId, proposal_index: ProposalIndex, verdict: bool, salt: [u8; 32]) -> Result<u32, DispatchError> {
+			// Check failure conditions
+			let referendum = Referenda::<T>::get(proposal_index).ok_or(Error::<T>::NoProposalToVoteFor)?;
+			ensure!(frame_system::Pallet::<T>::block_number() >= Self::reveal_start(&referendum), Error::<T>::NotInRevealPhase);
+			let commitment = Commitments::<T>::get(proposal_index, &sender).ok_or(Error::<T>::NoCommitment)?;
+			ensure!(T::Hashing::hash_of(&(&sender, verdict, salt)) == commitment.hash, Error::<T>::CommitmentMismatch);
+			// The commitment's place under MaxVoters goes to the ballot
+			Commitments::<T>::remove(proposal_index, &sender);
+			CommitmentCount::<T>::mutate(proposal_index, |count| *count = count.saturating_sub(1));
+			Self::cast_ballot(sender, proposal_index, referendum, verdict, commitment.power, true)
+		}
+
+		// First block of a secret referendum's reveal phase, halfway through its voting window
+		fn reveal_start(referendum: &ReferendumInfo<T>) -> T::BlockNumber {
+			referendum.start + (T::BlocksPerVote::get() / 2).into()
+		}
+
+		// Records the sender's ballot, and with `follow_delegations` the ballots of their delegators, and updates the tally
+		fn cast_ballot(
+			sender: T::AccountId,
+			proposal_index: ProposalIndex,
+			mut referendum: ReferendumInfo<T>,
+			verdict: bool,
+			voter_power: u128,
+			follow_delegations: bool,
+		) -> Result<u32, DispatchError> {
+			let mut current_tally = referendum.tally;
+			// If the sender already has a ballot on this referendum, their own or one cast by a delegate, take it back
+			// out of the tally first
+			let previous = Votes::<T>::get(proposal_index, &sender);
+			match previous {
+				Some(ref old_vote) => Self::remove_from_tally(&mut current_tally, old_vote),
+				None => {
+					ensure!(Self::has_room(proposal_index, &referendum), Error::<T>::TooManyVoters);
+					referendum.voters += 1;
+				},
+			}
+			// Add voter power to the tally of whichever side they supported
+			let ballot = VoteRecord { aye: verdict, power: voter_power, via: None };
+			Self::add_to_tally(&mut current_tally, &ballot);
+			Votes::<T>::insert(proposal_index, &sender, ballot);
+			Self::extend_vote_lock(&sender, referendum.end);
+			// Count everyone delegating to the sender on the same side
+			let delegators = if follow_delegations {
+				Self::cast_delegated_ballots(proposal_index, referendum.end, &sender, verdict, &mut current_tally, &mut referendum.voters)?
+			} else {
+				0
+			};
+			referendum.tally = current_tally;
+			Referenda::<T>::insert(proposal_index, referendum);
+			// Send success events
+			if previous.is_some() {
+				Self::deposit_event(Event::VoteChanged(sender.clone(), proposal_index, verdict, voter_power));
+			} else {
+				Self::deposit_event(Event::VoteRecorded(sender.clone(), proposal_index, verdict, voter_power));
+			}
+			Self::deposit_event(Event::Voted(sender.clone(), proposal_index, current_tally));
+			Ok(delegators)
+		}
+
+		// Walks the delegation tree below `delegate` and records a ballot for every delegator that hasn't voted on this
+		// referendum themselves. A delegator's own ballot also stops the walk down their branch, since their delegators
+		// follow them instead. Delegators without reserves cast nothing but still pass the vote on down their branch.
+		// Delegators that left the voter set have their delegations cleared and are skipped. Once the referendum has
+		// MaxVoters ballots and commitments, delegators without a ballot yet aren't counted.
+		fn cast_delegated_ballots(
+			proposal_index: ProposalIndex,
+			end: T::BlockNumber,
+			delegate: &T::AccountId,
+			verdict: bool,
+			tally: &mut (u128, u128),
+			voters: &mut u32,
+		) -> Result<u32, DispatchError> {
+			let mut walked: u32 = 0;
+			let commitments = CommitmentCount::<T>::get(proposal_index);
+			let mut frontier: Vec<T::AccountId> = sp_std::vec![delegate.clone()];
+			for _ in 0..T::MaxDelegationDepth::get() {
+				let mut next = Vec::new();
+				for account in frontier.iter() {
+					for delegator in Delegators::<T>::get(account).into_iter() {
+						walked += 1;
+						if T::Identity::get_voter_from_set(delegator.clone()).is_none() {
+							Self::clear_delegations(&delegator);
+							continue;
+						}
+						if !ReserveSet::<T>::contains_key(delegator.clone()) {
+							next.push(delegator);
+							continue;
+						}
+						match Votes::<T>::get(proposal_index, &delegator) {
+							Some(VoteRecord { via: None, .. }) => continue,
+							Some(old_vote) => Self::remove_from_tally(tally, &old_vote),
+							None if voters.saturating_add(commitments) >= T::MaxVoters::get() => {
+								next.push(delegator);
+								continue;
+							},
+							None => *voters += 1,
+						}
+						let power = Self::calc_voter_power_from_reserve(ReserveSet::<T>::get(delegator.clone()))?;
+						let ballot = VoteRecord { aye: verdict, power, via: Some(delegate.clone()) };
+						Self::add_to_tally(tally, &ballot);
+						Votes::<T>::insert(proposal_index, &delegator, ballot);
+						Self::extend_vote_lock(&delegator, end);
+						next.push(delegator);
+					}
+				}
+				if next.is_empty() {
+					break;
+				}
+				frontier = next;
+			}
+			Ok(walked)
+		}
+
+		// Keeps `who`'s reserve locked until at least `until`, when the referendum their ballot is on gets finalized
+		fn extend_vote_lock(who: &T::AccountId, until: T::BlockNumber) {
+			VoteLocks::<T>::mutate(who, |locked_until| match locked_until {
+				Some(current) if *current >= until => {},
+				_ => *locked_until = Some(until),
+			});
+		}
+
+		fn add_to_tally(tally: &mut (u128, u128), vote: &VoteRecord<T::AccountId>) {
+			if vote.aye {
+				tally.0 = tally.0.saturating_add(vote.power);
+			} else {
+				tally.1 = tally.1.saturating_add(vote.power);
+			}
+		}
+
+		fn remove_from_tally(tally: &mut (u128, u128), vote: &VoteRecord<T::AccountId>) {
+			if vote.aye {
+				tally.0 = tally.0.saturating_sub(vote.power);
 			} else {
-				current_tally.1 += voter_power;
+				tally.1 = tally.1.saturating_sub(vote.power);
 			}
-			Tally::<T>::put(current_tally);
+		}
+
+		fn delegate_impl(sender: T::AccountId, to: T::AccountId) -> Result<(), DispatchError> {
+			// Check failure conditions
+			ensure!(sender != to, Error::<T>::DelegatedToSelf);
+			ensure!(T::Identity::get_voter_from_set(sender.clone()).is_some(), Error::<T>::NotInVoterSet);
+			ensure!(T::Identity::get_voter_from_set(to.clone()).is_some(), Error::<T>::DelegateNotInVoterSet);
+			// Follow the delegate's chain to make sure it doesn't lead back to the sender, and that the chain starting at
+			// the sender stays within MaxDelegationDepth
+			let mut chain = sp_std::vec![to.clone()];
+			let mut next = Delegations::<T>::get(&to);
+			let mut chain_length: u32 = 1;
+			while let Some(account) = next {
+				ensure!(account != sender, Error::<T>::DelegationLoop);
+				chain_length += 1;
+				ensure!(chain_length <= T::MaxDelegationDepth::get(), Error::<T>::DelegationChainTooLong);
+				next = Delegations::<T>::get(&account);
+				chain.push(account);
+			}
+			// The sender brings everyone delegating to them along. Accounts on both the old and the new chain keep their count
+			let moved = DelegatedCount::<T>::get(&sender).saturating_add(1);
+			let old_delegate = Delegations::<T>::get(&sender);
+			let old_chain = old_delegate.as_ref().map(Self::delegation_chain).unwrap_or_default();
+			for account in chain.iter().filter(|account| !old_chain.contains(*account)) {
+				ensure!(DelegatedCount::<T>::get(account).saturating_add(moved) <= T::MaxDelegators::get(), Error::<T>::TooManyDelegators);
+			}
+			// Replace any earlier delegation
+			if let Some(old_delegate) = old_delegate {
+				Delegators::<T>::mutate(&old_delegate, |delegators| delegators.retain(|d| *d != sender));
+				Self::update_delegated_counts(&old_chain, moved, false);
+			}
+			Delegators::<T>::try_append(&to, sender.clone()).map_err(|()| Error::<T>::TooManyDelegators)?;
+			Self::update_delegated_counts(&chain, moved, true);
+			Delegations::<T>::insert(&sender, &to);
 			// Send success event
-			Self::deposit_event(Event::VotedOnCurrentProposal(sender.clone(), current_tally));
+			Self::deposit_event(Event::Delegated(sender, to));
 			Ok(())
 		}
 
-		// Calculate voter power as square root of reserve. Fails if reserve is somehow negative
-		fn calc_voter_power_from_reserve(reserve: CurrencyAmount<T>) -> Result<u128, Error::<T>> {
-			reserve.integer_sqrt().try_into().map_err(|_err| Error::<T>::BalanceToVoteConvertFailed)
+		fn undelegate_impl(sender: T::AccountId) -> Result<(), DispatchError> {
+			let delegate = Delegations::<T>::take(&sender).ok_or(Error::<T>::NotDelegating)?;
+			Delegators::<T>::mutate(&delegate, |delegators| delegators.retain(|d| *d != sender));
+			let moved = DelegatedCount::<T>::get(&sender).saturating_add(1);
+			Self::update_delegated_counts(&Self::delegation_chain(&delegate), moved, false);
+			// Send success event
+			Self::deposit_event(Event::Undelegated(sender));
+			Ok(())
 		}
 
-		fn finalize_current_vote() {
-			// Getting current proposal if any and removing from storage
-			let mut proposers = Proposers::<T>::get();
-			let mut proposals = Proposals::<T>::get();
-			if proposals.len() == 0 || proposers.len() == 0 { 
-				return; 
+		// Drops the delegation from `who` and every delegation to `who`. Used once `who` is no longer in the voter set.
+		// Returns the number of delegations to `who` that were dropped
+		pub(crate) fn clear_delegations(who: &T::AccountId) -> u32 {
+			let delegate = Delegations::<T>::take(who);
+			let moved = DelegatedCount::<T>::take(who).saturating_add(1);
+			if let Some(ref delegate) = delegate {
+				Delegators::<T>::mutate(delegate, |delegators| delegators.retain(|d| d != who));
+				Self::update_delegated_counts(&Self::delegation_chain(delegate), moved, false);
+			}
+			let delegators = Delegators::<T>::take(who);
+			for delegator in delegators.iter() {
+				Delegations::<T>::remove(delegator);
 			}
-			let proposal_bounded = proposals.get(0).expect("Already checked length").clone();
-			let proposer = proposers.get(0).expect("Same as proposals length").clone(); // Grab account id from proposal queue
-			proposals.remove(0);
-			proposers.remove(0);
-			Proposals::<T>::set(proposals);
-			Proposers::<T>::set(proposers);
+			if delegate.is_some() || !delegators.is_empty() {
+				Self::deposit_event(Event::DelegationsCleared(who.clone()));
+			}
+			delegators.len() as u32
+		}
 
-			// Getting current tally and resetting it
-			let tally = Tally::<T>::take();
-			let twice_nay = match tally.1.checked_mul(2) {
-				Some(x) => x,
-				None => u128::MAX // Sensable default
-			};
-			let yae_and_nay = match tally.0.checked_add(tally.1) {
-				Some(x) => x,
-				None => 0
+		// `delegate` followed by every account up its delegation chain
+		fn delegation_chain(delegate: &T::AccountId) -> Vec<T::AccountId> {
+			let mut chain = sp_std::vec![delegate.clone()];
+			let mut next = Delegations::<T>::get(delegate);
+			while let Some(account) = next {
+				if chain.len() as u32 > T::MaxDelegationDepth::get() {
+					break;
+				}
+				next = Delegations::<T>::get(&account);
+				chain.push(account);
+			}
+			chain
+		}
+
+		// Adds or takes `moved` delegators to or from every account on a delegation chain
+		fn update_delegated_counts(chain: &[T::AccountId], moved: u32, add: bool) {
+			for account in chain {
+				DelegatedCount::<T>::mutate(account, |count| {
+					*count = if add { count.saturating_add(moved) } else { count.saturating_sub(moved) }
+				});
+			}
+		}
+
+		// Calculate voter power from reserve along the configured curve. Fails if reserve doesn't fit in a u128
+		// Votes a voice credit budget of `reserve` buys on one referendum. A reserve too large for a u128 counts for
+		// nothing, as it couldn't be spent either
+		pub(crate) fn credit_votes_from_reserve(reserve: CurrencyAmount<T>) -> u128 {
+			let credits: u128 = reserve.try_into().unwrap_or_default();
+			credits.integer_sqrt()
+		}
+
+		// Moves an account's share of TotalVotingPower and TotalCreditVotes from its old reserve to its new one
+		fn update_electorate(old_reserve: CurrencyAmount<T>, new_reserve: CurrencyAmount<T>, old_power: u128, new_power: u128) {
+			TotalVotingPower::<T>::mutate(|total| *total = total.saturating_sub(old_power).saturating_add(new_power));
+			let old_votes = Self::credit_votes_from_reserve(old_reserve);
+			let new_votes = Self::credit_votes_from_reserve(new_reserve);
+			TotalCreditVotes::<T>::mutate(|total| *total = total.saturating_sub(old_votes).saturating_add(new_votes));
+		}
+
+		pub(crate) fn calc_voter_power_from_reserve(reserve: CurrencyAmount<T>) -> Result<u128, Error::<T>> {
+			let reserve: u128 = reserve.try_into().map_err(|_err| Error::<T>::BalanceToVoteConvertFailed)?;
+			Ok(T::VotingPowerCurve::power(reserve))
+		}
+
+		// Decode a stored proposal call and dispatch it with the configured execution origin in its own storage
+		// transaction, so a failing call leaves nothing behind. Also returns the weight the call used
+		fn execute_call(encoded_call: &[u8]) -> (DispatchResult, Weight) {
+			let call = match <T as Config>::Call::decode(&mut &encoded_call[..]) {
+				Ok(call) => call,
+				Err(_) => return (Err(Error::<T>::UndecodableCall.into()), 0),
 			};
-			// If > 2/3 approval and over participation threshold then pass
-			if tally.0 > twice_nay && yae_and_nay > T::ParticipationThreshold::get() {
-				// Use proposal to trigger proposal action if any
-				if proposal_bounded.to_vec() == b"mint a kitty".to_vec() {
-					let origin = OriginFor::<T>::root();
-					T::Kitties::free_create_kitty(origin, proposer).unwrap(); // Honestly don't know what to do with this return value. No time to solve
+			let info = call.get_dispatch_info();
+			let mut weight = info.weight;
+			let result = with_transaction(|| {
+				let result = call.dispatch(T::ExecutionOrigin::get());
+				weight = extract_actual_weight(&result, &info);
+				match result {
+					Ok(_) => TransactionOutcome::Commit(Ok(())),
+					Err(e) => TransactionOutcome::Rollback(Err(e.error)),
+				}
+			});
+			(result, weight)
+		}
+
+		// Dispatches the call of a passed proposal whose enactment block has come. Returns the weight used, including the call
+		pub(crate) fn enact(index: ProposalIndex, encoded_call: &[u8]) -> Weight {
+			EnactmentBlock::<T>::remove(index);
+			if let Some(proposal) = Proposals::<T>::get(index) {
+				Self::release_description(&proposal);
+			}
+			let (result, call_weight) = Self::execute_call(encoded_call);
+			match result {
+				Ok(()) => {
+					Self::note_execution(index, ExecutionOutcome::Succeeded);
+					Self::deposit_event(Event::ProposalExecuted { proposal: index, result });
+				}
+				Err(error) => {
+					Self::note_execution(index, ExecutionOutcome::Failed);
+					Self::deposit_event(Event::ProposalExecutionFailed { proposal: index, error });
+				}
+			}
+			T::WeightInfo::enact(encoded_call.len() as u32).saturating_add(call_weight)
+		}
+
+		// Records what became of a passed proposal's call, if its archive record hasn't been pruned yet
+		fn note_execution(index: ProposalIndex, execution: ExecutionOutcome) {
+			Archive::<T>::mutate(index, |record| {
+				if let Some(record) = record {
+					record.execution = Some(execution);
+				}
+			});
+		}
+
+		// Whether one more account can get a ballot or a commitment on the referendum within MaxVoters
+		fn has_room(index: ProposalIndex, referendum: &ReferendumInfo<T>) -> bool {
+			referendum.voters.saturating_add(CommitmentCount::<T>::get(index)) < T::MaxVoters::get()
+		}
+
+		// Takes a referendum out of storage along with up to MaxVoters of the ballots recorded for it. Any left over are
+		// cleared by on_idle
+		fn take_referendum(index: ProposalIndex) -> Option<ReferendumInfo<T>> {
+			let referendum = Referenda::<T>::take(index)?;
+			OpenReferenda::<T>::mutate(|open| open.retain(|(open_index, _)| *open_index != index));
+			let votes = Votes::<T>::clear_prefix(index, T::MaxVoters::get(), None);
+			let credit_ballots = CreditBallots::<T>::clear_prefix(index, T::MaxVoters::get(), None);
+			if votes.maybe_cursor.is_some() || credit_ballots.maybe_cursor.is_some() {
+				StaleBallots::<T>::insert(index, ());
+			}
+			Some(referendum)
+		}
+
+		// Drops up to MaxVoters of the commitments left on a referendum without penalizing them. Any left over are
+		// cleared by on_idle
+		fn clear_commitments(index: ProposalIndex) {
+			CommitmentCount::<T>::remove(index);
+			if Commitments::<T>::clear_prefix(index, T::MaxVoters::get(), None).maybe_cursor.is_some() {
+				StaleBallots::<T>::insert(index, ());
+			}
+		}
+
+		// Clears what is left of the ballots and commitments of closed referenda, one referendum after another, within
+		// `limit`. Returns the weight used
+		fn clear_stale_ballots(limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			// Finding the next referendum, and removing it from StaleBallots once it is done
+			let per_referendum = db_weight.reads_writes(1, 1);
+			// Reading and removing one key
+			let per_key = db_weight.reads_writes(1, 1).max(1);
+			let mut used: Weight = 0;
+			let maps: [fn(ProposalIndex, u32, Option<&[u8]>) -> frame_support::sp_io::MultiRemovalResults; 3] = [
+				Votes::<T>::clear_prefix::<ProposalIndex>,
+				CreditBallots::<T>::clear_prefix::<ProposalIndex>,
+				Commitments::<T>::clear_prefix::<ProposalIndex>,
+			];
+			while used.saturating_add(per_referendum).saturating_add(per_key) <= limit {
+				used = used.saturating_add(per_referendum);
+				let index = match StaleBallots::<T>::iter_keys().next() {
+					Some(index) => index,
+					None => break,
+				};
+				let mut finished = true;
+				for clear_prefix in maps.iter() {
+					let keys = ((limit - used) / per_key).min(u32::MAX as Weight) as u32;
+					if keys == 0 {
+						finished = false;
+						break;
+					}
+					let removed = clear_prefix(index, keys, None);
+					used = used.saturating_add(per_key.saturating_mul(removed.loops as Weight));
+					finished &= removed.maybe_cursor.is_none();
 				}
+				if !finished {
+					break;
+				}
+				StaleBallots::<T>::remove(index);
+			}
+			used
+		}
+
+		// Slashes UnrevealedPenalty of the reserve committed with every commitment left on the referendum, out of the
+		// voter's voting funds. Returns the number of commitments penalized
+		fn penalize_unrevealed(index: ProposalIndex) -> u32 {
+			let mut unrevealed: u32 = 0;
+			CommitmentCount::<T>::remove(index);
+			let commitments: Vec<(T::AccountId, Commitment<T::Hash, CurrencyAmount<T>>)> = Commitments::<T>::drain_prefix(index)
+				.take(T::MaxVoters::get() as usize)
+				.collect();
+			if Commitments::<T>::iter_key_prefix(index).next().is_some() {
+				StaleBallots::<T>::insert(index, ());
+			}
+			for (who, commitment) in commitments {
+				unrevealed += 1;
+				let penalty = Self::slash_voting_funds(&who, T::UnrevealedPenalty::get() * commitment.reserve);
+				if penalty.is_zero() {
+					continue;
+				}
+				Self::deposit_event(Event::UnrevealedVotePenalized(who, index, penalty));
+			}
+			unrevealed
+		}
+
+		// Slashes up to `amount` of the account's voting funds, out of its reserve first and then out of what's still
+		// unbonding, latest first. Only free balance is slashed, so funds the account has reserved elsewhere, such as
+		// proposal deposits, are left alone even when the voting lock overlaps them. Returns the amount slashed
+		fn slash_voting_funds(who: &T::AccountId, amount: CurrencyAmount<T>) -> CurrencyAmount<T> {
+			let reserve = ReserveSet::<T>::get(who.clone());
+			let amount = amount
+				.min(reserve.saturating_add(Self::unbonding_total(who)))
+				.min(T::Token::free_balance(who));
+			if amount.is_zero() {
+				return amount;
+			}
+			let (imbalance, _) = T::Token::slash(who, amount);
+			let slashed = imbalance.peek();
+			T::Slash::on_unbalanced(imbalance);
+			let from_reserve = slashed.min(reserve);
+			let remaining = reserve.saturating_sub(from_reserve);
+			let old_power = Self::calc_voter_power_from_reserve(reserve).unwrap_or(0);
+			let new_power = Self::calc_voter_power_from_reserve(remaining).unwrap_or(0);
+			if remaining.is_zero() {
+				ReserveSet::<T>::remove(who.clone());
+			} else {
+				ReserveSet::<T>::insert(who.clone(), remaining);
+			}
+			Self::update_electorate(reserve, remaining, old_power, new_power);
+			let mut left = slashed.saturating_sub(from_reserve);
+			if !left.is_zero() {
+				let mut chunks = Unbonding::<T>::get(who);
+				for chunk in chunks.iter_mut().rev() {
+					let cut = left.min(chunk.amount);
+					chunk.amount = chunk.amount.saturating_sub(cut);
+					left = left.saturating_sub(cut);
+				}
+				chunks.retain(|chunk| !chunk.amount.is_zero());
+				if chunks.is_empty() {
+					Unbonding::<T>::remove(who);
+				} else {
+					Unbonding::<T>::insert(who, chunks);
+				}
+			}
+			Self::update_voting_lock(who);
+			slashed
+		}
+
+		// Closes a referendum whose voting window has ended, queueing the call of a passed proposal for enactment.
+		// Returns the weight used
+		pub(crate) fn finalize_referendum(index: ProposalIndex) -> Weight {
+			// Recount before the ballots are cleared
+			let capped_tally = Self::capped_tally(index);
+			let mut referendum = match Self::take_referendum(index) {
+				Some(referendum) => referendum,
+				None => return T::DbWeight::get().reads(1),
+			};
+			if let Some(tally) = capped_tally {
+				referendum.tally = tally;
+			}
+			let mut weight = T::WeightInfo::finalize_referendum(referendum.voters);
+			// capped_tally read the referendum and every ballot on it
+			if capped_tally.is_some() {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1 + referendum.voters as Weight));
+			}
+			// Commitments still around were never revealed
+			if referendum.secret {
+				let unrevealed = Self::penalize_unrevealed(index);
+				weight = weight.saturating_add(T::WeightInfo::penalize_unrevealed(unrevealed));
+			}
+			let proposal = match Proposals::<T>::get(index) {
+				Some(proposal) => proposal,
+				None => return weight,
+			};
+			let tally = referendum.tally;
+			let summary = Self::tally_summary(&referendum);
+			if Self::passes(&summary) {
 				// Send success event
-				Self::deposit_event(Event::VoteOnCurrentProposalPassed(proposal_bounded, tally));
+				Self::deposit_event(Event::ProposalPassed(index, proposal.title, tally));
+				Self::refund_deposit(index, &referendum);
+				Self::archive(index, &referendum, ProposalOutcome::Passed, None);
+				// Queue the proposal's call if it has one
+				if let Some(encoded_call) = proposal.call {
+					let at = frame_system::Pallet::<T>::block_number() + T::EnactmentPeriod::get().into();
+					Enactments::<T>::insert(at, index, encoded_call);
+					EnactmentBlock::<T>::insert(index, at);
+					Self::deposit_event(Event::EnactmentScheduled(index, at));
+				}
 			} else {
 				// Send failure event
-				Self::deposit_event(Event::CurrentProposalRejected(proposal_bounded, tally));
+				Self::deposit_event(Event::ProposalRejected(index, proposal.title, tally));
+				Self::archive(index, &referendum, ProposalOutcome::Rejected, None);
+				// Only proposals with a fair share of ayes get their deposit back
+				if summary.ayes > 0 && summary.ayes >= T::DepositRefundThreshold::get() * summary.turnout() {
+					Self::refund_deposit(index, &referendum);
+				} else {
+					Self::slash_deposit(index, &referendum);
+				}
+			}
+			weight
+		}
+
+		// Recounts the ballots of an open referendum so no account adds more than the curve's `max_power` of the
+		// turnout before capping. `None` if the curve counts ballots in full
+		fn capped_tally(index: ProposalIndex) -> Option<(u128, u128)> {
+			let (ayes, nays) = Referenda::<T>::get(index)?.tally;
+			let cap = T::VotingPowerCurve::max_power(ayes.saturating_add(nays))?;
+			Some(Votes::<T>::iter_prefix_values(index).fold((0, 0), |(ayes, nays), record| {
+				let power = record.power.min(cap);
+				if record.aye {
+					(ayes.saturating_add(power), nays)
+				} else {
+					(ayes, nays.saturating_add(power))
+				}
+			}))
+		}
+
+		// An open referendum with its tally capped as it would be if it were finalized now
+		fn capped_referendum(index: ProposalIndex) -> Option<ReferendumInfo<T>> {
+			let mut referendum = Referenda::<T>::get(index)?;
+			if let Some(tally) = Self::capped_tally(index) {
+				referendum.tally = tally;
+			}
+			Some(referendum)
+		}
+
+		fn tally_summary(referendum: &ReferendumInfo<T>) -> TallySummary {
+			TallySummary {
+				ayes: referendum.tally.0,
+				nays: referendum.tally.1,
+				voters: referendum.voters,
+				electorate: Self::electorate(),
+				members: T::Identity::voter_count(),
 			}
-			
+		}
+
+		// Most that the whole electorate could put behind one referendum, in the units ballots are tallied in. That's the
+		// voting power reserved when ballots are weighed by reserve, and the votes every budget buys when spent on the
+		// referendum alone when they're paid for with voice credits
+		pub(crate) fn electorate() -> u128 {
+			match T::Mode::get() {
+				VotingMode::SqrtOfReserve => TotalVotingPower::<T>::get(),
+				VotingMode::VoiceCredits => match T::VoiceCredits::get() {
+					CreditSource::Flat(credits) =>
+						u128::from(T::Identity::voter_count()).saturating_mul(credits.integer_sqrt()),
+					CreditSource::Reserve => TotalCreditVotes::<T>::get(),
+				},
+			}
+		}
+
+		// Pass if quorum was reached and the ayes won under the configured approval rule
+		fn passes(summary: &TallySummary) -> bool {
+			T::QuorumRule::quorum_reached(summary) && T::ApprovalRule::approved(summary)
+		}
 
-			
+		// Records how a referendum was closed on its proposal and in the archive, and schedules both to be pruned after
+		// ArchiveRetention blocks
+		fn archive(index: ProposalIndex, referendum: &ReferendumInfo<T>, outcome: ProposalOutcome, execution: Option<ExecutionOutcome>) {
+			let proposal = match Proposals::<T>::get(index) {
+				Some(proposal) => proposal,
+				None => return,
+			};
+			Proposals::<T>::insert(index, ProposalInfo { status: outcome.into(), ..proposal.clone() });
+			// A passed call keeps its description until it's enacted or cancelled
+			if outcome != ProposalOutcome::Passed || proposal.call.is_none() {
+				Self::release_description(&proposal);
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			let record = ArchivedProposal::<T> {
+				proposer: proposal.proposer,
+				text: proposal.title,
+				call_hash: proposal.call.as_ref().map(|call| T::Hashing::hash(&call[..])),
+				tally: referendum.tally,
+				voters: referendum.voters,
+				outcome,
+				execution,
+				finalized_at: now,
+			};
+			Archive::<T>::insert(index, record);
+			ArchiveExpiry::<T>::insert(now + T::ArchiveRetention::get().into(), index, ());
+		}
+
+		// Drops a proposal's reference to its description, once its referendum is closed and any call it queued is done
+		fn release_description(proposal: &ProposalInfo<T>) {
+			if let Some(hash) = proposal.description {
+				let refs = DescriptionRefs::<T>::get(hash).saturating_sub(1);
+				if refs == 0 {
+					DescriptionRefs::<T>::remove(hash);
+				} else {
+					DescriptionRefs::<T>::insert(hash, refs);
+				}
+			}
+		}
+
+		fn refund_deposit(index: ProposalIndex, referendum: &ReferendumInfo<T>) {
+			if let Some(proposal) = Proposals::<T>::get(index) {
+				T::Token::unreserve(&proposal.proposer, referendum.deposit);
+				Self::deposit_event(Event::DepositRefunded(index, proposal.proposer, referendum.deposit));
+			}
+		}
+
+		fn slash_deposit(index: ProposalIndex, referendum: &ReferendumInfo<T>) {
+			if let Some(proposal) = Proposals::<T>::get(index) {
+				let (imbalance, _) = T::Token::slash_reserved(&proposal.proposer, referendum.deposit);
+				T::Slash::on_unbalanced(imbalance);
+				Self::deposit_event(Event::DepositSlashed(index, proposal.proposer, referendum.deposit));
+			}
 		}
 	}
-}
\ No newline at end of file
+
+	// Queries backing the QuadraticVotingApi runtime API
+	impl<T: Config> Pallet<T> {
+		/// Every open referendum, in submission order
+		pub fn open_referenda_status() -> Vec<ReferendumStatus<T::AccountId, T::BlockNumber>> {
+			OpenReferenda::<T>::get().iter()
+				.filter_map(|(index, _)| Some((*index, Self::capped_referendum(*index)?, Proposals::<T>::get(index)?)))
+				.map(|(index, referendum, proposal)| ReferendumStatus {
+					index,
+					proposer: proposal.proposer,
+					text: proposal.title.to_vec(),
+					has_call: proposal.call.is_some(),
+					start: referendum.start,
+					end: referendum.end,
+					ayes: referendum.tally.0,
+					nays: referendum.tally.1,
+					voters: referendum.voters,
+					projected_pass: Self::passes(&Self::tally_summary(&referendum)),
+				})
+				.collect()
+		}
+
+		/// Whether an open referendum would pass if it were finalized now. `None` if it isn't open
+		pub fn projected_outcome(index: ProposalIndex) -> Option<bool> {
+			Self::capped_referendum(index).map(|referendum| Self::passes(&Self::tally_summary(&referendum)))
+		}
+
+		/// The power `who` votes with, split into their own and what their delegators would add to a ballot on a
+		/// referendum none of them voted on yet
+		pub fn voting_power_of(who: T::AccountId) -> VotingPowerInfo {
+			let own = Self::calc_voter_power_from_reserve(ReserveSet::<T>::get(who.clone())).unwrap_or(0);
+			let mut delegated: u128 = 0;
+			let mut frontier: Vec<T::AccountId> = sp_std::vec![who];
+			for _ in 0..T::MaxDelegationDepth::get() {
+				let mut next = Vec::new();
+				for account in frontier.iter() {
+					for delegator in Delegators::<T>::get(account).into_iter() {
+						if T::Identity::get_voter_from_set(delegator.clone()).is_none() {
+							continue;
+						}
+						let power = Self::calc_voter_power_from_reserve(ReserveSet::<T>::get(delegator.clone())).unwrap_or(0);
+						delegated = delegated.saturating_add(power);
+						next.push(delegator);
+					}
+				}
+				if next.is_empty() {
+					break;
+				}
+				frontier = next;
+			}
+			VotingPowerInfo { own, delegated }
+		}
+
+		/// Blocks left until the next open referendum is finalized. `None` if no referendum is open
+		pub fn blocks_until_next_finalization() -> Option<T::BlockNumber> {
+			let now = frame_system::Pallet::<T>::block_number();
+			OpenReferenda::<T>::get().iter()
+				.map(|(_, end)| *end)
+				.min()
+				.map(|end| end.saturating_sub(now))
+		}
+	}
+}
+
+// Delegations to and from a member go as soon as the identity pallet removes them, rather than when a vote next walks
+// past them
+impl<T: Config> brads_soft_coupling::OnMemberRemoved<T::AccountId> for Pallet<T> {
+	fn on_member_removed(who: &T::AccountId) -> Weight {
+		let delegators = Pallet::<T>::clear_delegations(who);
+		// The member's delegation and count, their delegate's and their own delegator lists, the count of everyone up
+		// their chain, then one delegation per delegator
+		let chain = T::MaxDelegationDepth::get() as Weight;
+		T::DbWeight::get().reads_writes(4 + chain * 2, 4 + chain + delegators as Weight)
+	}
+
+	fn max_weight() -> Weight {
+		let chain = T::MaxDelegationDepth::get() as Weight;
+		T::DbWeight::get().reads_writes(4 + chain * 2, 4 + chain + T::MaxDelegators::get() as Weight)
+	}
+}
+
+/// Serializes u128 fields of the runtime API types as decimal strings, like `RuntimeDispatchInfo::partial_fee`, since
+/// JavaScript numbers lose precision past 2^53
+#[cfg(feature = "std")]
+mod serde_as_string {
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer, T: std::fmt::Display>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&value.to_string())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>, T: std::str::FromStr>(deserializer: D) -> Result<T, D::Error> {
+		let value = String::deserialize(deserializer)?;
+		value.parse::<T>().map_err(|_| serde::de::Error::custom("Parse from string failed"))
+	}
+}
+
+/// `ExecutionOrigin` which dispatches passed call proposals as root
+pub struct DispatchAsRoot<T>(PhantomData<T>);
+
+impl<T: frame_system::Config> Get<T::Origin> for DispatchAsRoot<T> {
+	fn get() -> T::Origin {
+		frame_system::RawOrigin::Root.into()
+	}
+}
+
+/// Benchmark setup the identity pallet has to provide, kept out of `IdentityInterface` so production code can't reach it
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId> {
+	/// Adds `who` to the voter set
+	fn add_member(who: &AccountId);
+}
@@ -0,0 +1,274 @@
+// Storage migrations for the quadratic voting pallet. Each is run from `on_runtime_upgrade` and only
+// does anything when the on-chain storage version is behind.
+
+use crate::pallet::{
+	Commitment, Commitments, Config, CurrencyAmount, DescriptionRefs, EnactmentBlock, OpenReferenda, Pallet, ProposalInfo, ProposalStatus, Proposals, ReferendumCount, Referenda,
+	ReferendumInfo, ReserveSet, TotalCreditVotes, TotalVotingPower, VOTING_LOCK_ID,
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+use frame_support::{
+	pallet_prelude::*,
+	migration::{storage_iter, take_storage_value},
+	sp_runtime::traits::Zero,
+	storage::StoragePrefixedMap,
+	traits::{GetStorageVersion, LockableCurrency, PalletInfoAccess, ReservableCurrency, WithdrawReasons},
+};
+use sp_std::vec::Vec;
+
+pub mod v1 {
+	use super::*;
+
+	/// Version 0 reserved voting funds with `Token::reserve`. Unreserves each `ReserveSet` entry and
+	/// puts the same amount under `VOTING_LOCK_ID` instead.
+	///
+	/// `ReserveSet` keys are hashed with a non-reversible hasher, so only the Blake2 128 hash of each
+	/// account is known. `frame_system::Account` keys are that same hash followed by the account, so
+	/// the account is read back from the first system account key after the hash. Only the pallet's
+	/// own reserves are visited. An entry whose account has no system account left is logged and
+	/// skipped, and its reserve stays where it is.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			return T::DbWeight::get().reads(1)
+		}
+		let reserves: Vec<(Vec<u8>, CurrencyAmount<T>)> =
+			storage_iter(<Pallet<T> as PalletInfoAccess>::name().as_bytes(), b"ReserveSet").collect();
+		let account_prefix = frame_system::Account::<T>::final_prefix();
+		let mut migrated: u64 = 0;
+		let mut skipped: u64 = 0;
+		for (hash, amount) in reserves.iter() {
+			let start = [&account_prefix[..], &hash[..]].concat();
+			let who = match frame_system::Account::<T>::iter_keys_from(start.clone()).next() {
+				Some(who) if frame_system::Account::<T>::hashed_key_for(&who).starts_with(&start) => who,
+				_ => {
+					log::warn!(
+						target: "runtime::quadratic-voting",
+						"v1: no system account for the ReserveSet entry with hash {:?}, leaving its reserve in place",
+						hash,
+					);
+					skipped += 1;
+					continue
+				},
+			};
+			T::Token::unreserve(&who, *amount);
+			T::Token::set_lock(VOTING_LOCK_ID, &who, *amount, WithdrawReasons::TRANSFER);
+			migrated += 1;
+		}
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!(target: "runtime::quadratic-voting", "v1: moved {} reserves onto the voting lock, skipped {}", migrated, skipped);
+		// Per reserve, migrated or skipped: the ReserveSet entry and the system account key after its
+		// hash. Per migrated account: the account data on unreserve and the lock list on set_lock
+		T::DbWeight::get().reads_writes(1 + (migrated + skipped) * 2 + migrated * 2, 1 + migrated * 3)
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Version 0 kept a single queue of proposals: the texts in a `Proposals` value, their proposers in a parallel
+	/// `Proposers` value, and the tally of the proposal at the head of the queue in `Tally`. The head was finalized
+	/// every BlocksPerVote blocks and the next one took its place. Opens a referendum for each queued proposal, in
+	/// queue order and with the end block the queue would have given it, so the head keeps its tally and closes at
+	/// the next boundary, and each later proposal closes BlocksPerVote blocks after the one before. Version 0 reserved
+	/// no proposal deposit and recorded no ballots, so the referenda start with neither. Version 0 acted on the text
+	/// "mint a kitty" when it passed, which isn't carried over, since proposals now carry an encoded call. The old
+	/// keys are removed.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 2 {
+			return T::DbWeight::get().reads(1)
+		}
+		let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+		// The version 0 `Proposals` value lives at the prefix of the `Proposals` map, which no map entry uses
+		let texts = take_storage_value::<Vec<Vec<u8>>>(pallet, b"Proposals", &[]).unwrap_or_default();
+		let proposers = take_storage_value::<Vec<T::AccountId>>(pallet, b"Proposers", &[]).unwrap_or_default();
+		let tally = take_storage_value::<(u128, u128)>(pallet, b"Tally", &[]).unwrap_or_default();
+		let now = frame_system::Pallet::<T>::block_number();
+		let blocks_per_vote: T::BlockNumber = T::BlocksPerVote::get().into();
+		let mut end = now - now % blocks_per_vote + blocks_per_vote;
+		let mut index = ReferendumCount::<T>::get();
+		let mut open = OpenReferenda::<T>::get();
+		let mut migrated: u64 = 0;
+		// A text without a proposer can't be opened, as the queue never finalized it either
+		for (text, proposer) in texts.into_iter().zip(proposers.into_iter()) {
+			if open.try_push((index, end)).is_err() {
+				break;
+			}
+			Proposals::<T>::insert(index, ProposalInfo {
+				proposer,
+				title: BoundedVec::truncate_from(text),
+				description: None,
+				call: None,
+				submitted: now,
+				status: ProposalStatus::Voting,
+			});
+			Referenda::<T>::insert(index, ReferendumInfo {
+				deposit: Zero::zero(),
+				start: now,
+				end,
+				tally: if migrated == 0 { tally } else { (0, 0) },
+				voters: 0,
+				secret: false,
+			});
+			index += 1;
+			end += blocks_per_vote;
+			migrated += 1;
+		}
+		OpenReferenda::<T>::put(open);
+		ReferendumCount::<T>::put(index);
+		StorageVersion::new(2).put::<Pallet<T>>();
+		// The three old values, the count and the open list, then per proposal its info and referendum
+		T::DbWeight::get().reads_writes(6, 6 + migrated * 2)
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Version 2 started keeping `TotalVotingPower` without counting the voting power already reserved, so turnout
+	/// biased rules and quorums saw a smaller electorate than there was. Sums the power of every `ReserveSet` entry
+	/// along the configured curve. A reserve too large for a u128 counts for nothing, as it couldn't be reserved now.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 3 {
+			return T::DbWeight::get().reads(1)
+		}
+		let mut reserves: u64 = 0;
+		let mut total: u128 = 0;
+		for reserve in ReserveSet::<T>::iter_values() {
+			let power = Pallet::<T>::calc_voter_power_from_reserve(reserve).unwrap_or_default();
+			total = total.saturating_add(power);
+			reserves += 1;
+		}
+		TotalVotingPower::<T>::put(total);
+		StorageVersion::new(3).put::<Pallet<T>>();
+		// The version, then per reserve its entry
+		T::DbWeight::get().reads_writes(1 + reserves, 2)
+	}
+}
+
+pub mod v4 {
+	use super::*;
+
+	#[derive(Decode)]
+	struct OldCommitment<Hash> {
+		hash: Hash,
+		power: u128,
+	}
+
+	/// Version 3 commitments didn't record the reserve they were made with, and the penalty for not revealing was
+	/// taken of the reserve at the end of the referendum. Gives each commitment the voter's current reserve, which is
+	/// what it would have been penalized on. Vote locks keep a reserve from shrinking while a commitment is open, so
+	/// it's at least what was committed.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 4 {
+			return T::DbWeight::get().reads(1)
+		}
+		let mut commitments: u64 = 0;
+		Commitments::<T>::translate::<OldCommitment<T::Hash>, _>(|_, who, old| {
+			commitments += 1;
+			Some(Commitment { hash: old.hash, power: old.power, reserve: ReserveSet::<T>::get(who) })
+		});
+		StorageVersion::new(4).put::<Pallet<T>>();
+		// The version, then per commitment its entry and the voter's reserve
+		T::DbWeight::get().reads_writes(1 + commitments * 2, 1 + commitments)
+	}
+}
+
+pub mod v5 {
+	use super::*;
+
+	/// Version 4 weighed voice credit ballots against the voting power reserved, which is in different units. Sums the
+	/// votes a voice credit budget of each `ReserveSet` entry buys on one referendum into `TotalCreditVotes`.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 5 {
+			return T::DbWeight::get().reads(1)
+		}
+		let mut reserves: u64 = 0;
+		let mut total: u128 = 0;
+		for reserve in ReserveSet::<T>::iter_values() {
+			total = total.saturating_add(Pallet::<T>::credit_votes_from_reserve(reserve));
+			reserves += 1;
+		}
+		TotalCreditVotes::<T>::put(total);
+		StorageVersion::new(5).put::<Pallet<T>>();
+		// The version, then per reserve its entry
+		T::DbWeight::get().reads_writes(1 + reserves, 2)
+	}
+}
+
+pub mod v6 {
+	use super::*;
+
+	/// Version 5 let a description be cleared while proposals still referred to it. Counts the open referenda and
+	/// queued enactments whose proposal refers to each description into `DescriptionRefs`. Enactments whose proposal
+	/// has already been pruned from the archive aren't counted, as nothing would ever release them.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 6 {
+			return T::DbWeight::get().reads(1)
+		}
+		let open = OpenReferenda::<T>::get().into_iter().map(|(index, _)| index);
+		let queued: Vec<_> = EnactmentBlock::<T>::iter_keys().collect();
+		let mut proposals: u64 = 0;
+		let mut refs: u64 = 0;
+		for index in open.chain(queued.into_iter()) {
+			proposals += 1;
+			if let Some(hash) = Proposals::<T>::get(index).and_then(|proposal| proposal.description) {
+				DescriptionRefs::<T>::mutate(hash, |count| *count = count.saturating_add(1));
+				refs += 1;
+			}
+		}
+		StorageVersion::new(6).put::<Pallet<T>>();
+		// The version and the open list, then per proposal its enactment block or place in the list and its info, and
+		// per reference its count
+		T::DbWeight::get().reads_writes(2 + proposals * 2 + refs, 1 + refs)
+	}
+}
+
+/// Checked by try-runtime before the migrations run. The v1, v3 and v5 migrations walk every `ReserveSet` entry in the
+/// upgrade block, so they have to fit in one block between them
+#[cfg(feature = "try-runtime")]
+pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+	let version = Pallet::<T>::on_chain_storage_version();
+	let reserves = ReserveSet::<T>::count();
+	let per_reserve = reserves as u64;
+	let mut weight: Weight = 0;
+	if version < 1 {
+		// v1 migrating every reserve
+		weight = weight.saturating_add(T::DbWeight::get().reads_writes(1 + per_reserve * 4, 1 + per_reserve * 3));
+	}
+	if version < 3 {
+		weight = weight.saturating_add(T::DbWeight::get().reads_writes(1 + per_reserve, 2));
+	}
+	if version < 5 {
+		weight = weight.saturating_add(T::DbWeight::get().reads_writes(1 + per_reserve, 2));
+	}
+	ensure!(
+		weight <= <T as frame_system::Config>::BlockWeights::get().max_block,
+		"Migrating ReserveSet doesn't fit in one block"
+	);
+	Pallet::<T>::set_temp_storage(reserves, "reserves");
+	Ok(())
+}
+
+/// Checked by try-runtime after the migrations ran: every reserve is still there and counted, and `TotalVotingPower`
+/// and `TotalCreditVotes` are their sums
+#[cfg(feature = "try-runtime")]
+pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+	ensure!(
+		Pallet::<T>::on_chain_storage_version() == Pallet::<T>::current_storage_version(),
+		"Storage version not updated"
+	);
+	let reserves: u32 = Pallet::<T>::get_temp_storage("reserves").ok_or("No ReserveSet count from pre_upgrade")?;
+	ensure!(ReserveSet::<T>::count() == reserves, "ReserveSet entries changed");
+	let mut total: u128 = 0;
+	let mut credit_votes: u128 = 0;
+	let mut counted: u32 = 0;
+	for reserve in ReserveSet::<T>::iter_values() {
+		total = total.saturating_add(Pallet::<T>::calc_voter_power_from_reserve(reserve).unwrap_or_default());
+		credit_votes = credit_votes.saturating_add(Pallet::<T>::credit_votes_from_reserve(reserve));
+		counted += 1;
+	}
+	ensure!(counted == reserves, "ReserveSet counter doesn't match its entries");
+	ensure!(TotalVotingPower::<T>::get() == total, "TotalVotingPower isn't the sum of ReserveSet");
+	ensure!(TotalCreditVotes::<T>::get() == credit_votes, "TotalCreditVotes isn't the sum of ReserveSet");
+	Ok(())
+}
@@ -1,11 +1,13 @@
 use crate::{self as quadratic_voting};
-use frame_support::traits::{ConstU16, ConstU32, ConstU128, ConstU64, Hooks};
+use crate::{CappedSqrtCurve, LinearCurve, LogCurve, SqrtCurve, VotingPowerCurve};
+use frame_support::{parameter_types, traits::{ConstU16, ConstU32, ConstU128, ConstU64, Hooks}};
 use frame_system as system;
 use pallet_balances;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
 };
 use pallet_randomness_collective_flip;
 
@@ -56,7 +58,7 @@ impl system::Config for Test {
 }
 
 impl pallet_balances::Config for Test {
-	type MaxLocks = ();
+	type MaxLocks = ConstU32<50>;
 	type MaxReserves = ();
 	type ReserveIdentifier = [u8; 8];
 	/// The type for recording an account's balance.
@@ -69,15 +71,96 @@ impl pallet_balances::Config for Test {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub BondSlash: Perbill = Perbill::from_percent(100);
+	pub TwoThirds: Perbill = Perbill::from_rational(2u32, 3u32);
+	pub OneThird: Perbill = Perbill::from_rational(1u32, 3u32);
+	pub UnrevealedPenalty: Perbill = Perbill::from_percent(10);
+	pub static SecretBallots: bool = false;
+	pub static Mode: quadratic_voting::VotingMode = quadratic_voting::VotingMode::SqrtOfReserve;
+	pub static VoiceCredits: quadratic_voting::CreditSource = quadratic_voting::CreditSource::Flat(100);
+	pub static PowerCurve: Curve = Curve::Sqrt;
+	pub static MaxVoters: u32 = 20;
+	pub static ProposalDeposit: u128 = 1_000;
+	pub MaxShare: Perbill = Perbill::from_percent(20);
+}
+
+/// The bundled voting power curves, so tests can switch between them
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Curve {
+	Sqrt,
+	Linear,
+	Log,
+	CappedSqrt,
+}
+
+/// Follows whichever curve `PowerCurve` is set to
+pub struct MockCurve;
+
+impl VotingPowerCurve for MockCurve {
+	fn power(reserve: u128) -> u128 {
+		match PowerCurve::get() {
+			Curve::Sqrt => SqrtCurve::power(reserve),
+			Curve::Linear => LinearCurve::power(reserve),
+			Curve::Log => LogCurve::power(reserve),
+			Curve::CappedSqrt => CappedSqrtCurve::<MaxShare>::power(reserve),
+		}
+	}
+
+	fn max_power(turnout: u128) -> Option<u128> {
+		match PowerCurve::get() {
+			Curve::Sqrt => SqrtCurve::max_power(turnout),
+			Curve::Linear => LinearCurve::max_power(turnout),
+			Curve::Log => LogCurve::max_power(turnout),
+			Curve::CappedSqrt => CappedSqrtCurve::<MaxShare>::max_power(turnout),
+		}
+	}
+}
+
 impl quadratic_voting::Config for Test {
 	type Event = Event;
 	type Token = Balances;
+	type Call = Call;
+	type ExecutionOrigin = quadratic_voting::DispatchAsRoot<Test>;
 	type MaxProposals = ConstU32<10>;
 	type MaxProposalLength = ConstU32<1_000>;
 	type BlocksPerVote = ConstU32<60>;
-	type ParticipationThreshold = ConstU128<10_000>;
+	type ApprovalRule = quadratic_voting::SuperMajority<TwoThirds>;
+	type QuorumRule = quadratic_voting::AbsolutePower<ConstU128<10_000>>;
+	type VotingPowerCurve = MockCurve;
 	type Identity = IdentityPallet;
-	type Kitties = SubstrateKitties;
+	type MaxDelegators = ConstU32<10>;
+	type MaxDelegationDepth = ConstU32<5>;
+	type VetoOrigin = frame_system::EnsureRoot<u64>;
+	type EnactmentPeriod = ConstU32<10>;
+	type ProposalDeposit = ProposalDeposit;
+	type MaxDescriptionLength = ConstU32<256>;
+	type DescriptionBaseDeposit = ConstU128<100>;
+	type DescriptionByteDeposit = ConstU128<10>;
+	type DepositRefundThreshold = OneThird;
+	type Slash = ();
+	type ArchiveRetention = ConstU32<100>;
+	type SecretBallots = SecretBallots;
+	type UnrevealedPenalty = UnrevealedPenalty;
+	type Mode = Mode;
+	type VoiceCredits = VoiceCredits;
+	type CreditRoundLength = ConstU32<100>;
+	type UnbondingPeriod = ConstU32<20>;
+	type MaxUnbondingChunks = ConstU32<3>;
+	type MaxVoters = MaxVoters;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl quadratic_voting::BenchmarkHelper<u64> for MockBenchmarkHelper {
+	fn add_member(who: &u64) {
+		IdentityPallet::force_add_member(Origin::root(), *who).expect("Benchmark accounts aren't members yet");
+	}
 }
 
 impl crypto_kitties::Config for Test {
@@ -89,7 +172,17 @@ impl crypto_kitties::Config for Test {
 
 impl identity_pallet::Config for Test {
 	type Event = Event;
-	type MinVouches = ConstU32<2>;
+	type MinVouches = ConstU32<10>;
+	type RemoveOrigin = frame_system::EnsureRoot<u64>;
+	type MembershipPeriod = ConstU32<1_000>;
+	type ReattestationVouches = ConstU32<0>;
+	type Currency = Balances;
+	type VouchBond = ConstU128<1_000>;
+	type BondMaturity = ConstU32<100>;
+	type BondSlash = BondSlash;
+	type Slash = ();
+	type OnMemberRemoved = QuadraticVoting;
+	type MaxVouchees = ConstU32<10>;
 }
 
 impl pallet_randomness_collective_flip::Config for Test {}
@@ -123,6 +216,7 @@ impl ExtBuilder {
 		let head = System::finalize();
 		System::set_block_number(System::block_number() + 1);
 		System::initialize(&System::block_number(), &head.parent_hash, &head.digest);
+		IdentityPallet::on_initialize(System::block_number());
 		QuadraticVoting::on_initialize(System::block_number());
 	}
    }
\ No newline at end of file
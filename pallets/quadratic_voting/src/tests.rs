@@ -1,14 +1,102 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{mock::*, Error, ExecutionOutcome, ProposalOutcome, ProposalStatus, VoteRecord, VotingMode, VotingPowerInfo, VOTING_LOCK_ID};
+use crate::{AbsolutePower, ApprovalRule, MemberTurnout, NegativeTurnoutBias, PositiveTurnoutBias, QuorumRule, SimpleMajority, SuperMajority, TallySummary};
+use crate::{CappedSqrtCurve, LinearCurve, LogCurve, SqrtCurve, VotingPowerCurve};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, parameter_types, traits::{ConstU128, Currency, GetStorageVersion, Hooks, ReservableCurrency, StorageVersion}};
+use sp_runtime::{traits::{BlakeTwo256, Hash}, Perbill};
 
 #[test]
 fn can_add_proposal() {
 	ExtBuilder::build().execute_with(|| {
 		let origin = Origin::signed(1);
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
-		assert_ok!(QuadraticVoting::add_proposal(origin, b"mint a kitty".to_vec()));
-		assert_eq!(QuadraticVoting::get_proposals()[0], b"mint a kitty".to_vec());
-		assert_eq!(QuadraticVoting::get_proposers()[0], 1);
+		assert_ok!(QuadraticVoting::add_proposal(origin, b"mint a kitty".to_vec(), None, None));
+		let proposal = QuadraticVoting::get_proposal(0).unwrap();
+		assert_eq!(proposal.title, b"mint a kitty".to_vec());
+		assert_eq!((proposal.proposer, proposal.submitted, proposal.status), (1, 2, ProposalStatus::Voting));
+		let referendum = QuadraticVoting::get_referendum(0).unwrap();
+		assert_eq!((referendum.start, referendum.end), (2, 62));
+		assert_eq!(QuadraticVoting::get_referendum_count(), 1);
+	});
+}
+
+#[test]
+fn proposals_refer_to_noted_descriptions() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		let description = b"A free kitty for whoever proposed it".to_vec();
+		let hash = BlakeTwo256::hash(&description);
+		assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), Some(hash), None), Error::<Test>::DescriptionNotNoted);
+		assert_noop!(QuadraticVoting::note_description(origin.clone(), vec![1; 257]), Error::<Test>::DescriptionTooLong);
+		assert_noop!(QuadraticVoting::note_description(Origin::signed(7), description.clone()), Error::<Test>::InsufficientDescriptionDeposit);
+		// The deposit is 100 plus 10 per byte in mock.rs
+		assert_ok!(QuadraticVoting::note_description(origin.clone(), description.clone()));
+		System::assert_last_event(Event::QuadraticVoting(crate::Event::DescriptionNoted(hash, 1, 460)));
+		assert_eq!(Balances::reserved_balance(1), 460);
+		assert_noop!(QuadraticVoting::note_description(Origin::signed(2), description.clone()), Error::<Test>::DescriptionAlreadyNoted);
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), Some(hash), None));
+		assert_eq!(QuadraticVoting::get_proposal(0).unwrap().description, Some(hash));
+		assert_eq!(QuadraticVoting::get_description(hash).unwrap().text.into_inner(), description);
+		// It can't be cleared while the referendum is open
+		assert_noop!(QuadraticVoting::clear_description(Origin::signed(2), hash), Error::<Test>::NotDescriptionDepositor);
+		assert_noop!(QuadraticVoting::clear_description(origin.clone(), hash), Error::<Test>::DescriptionInUse);
+		assert_ok!(QuadraticVoting::withdraw_proposal(origin.clone(), 0, 0));
+		assert_eq!(QuadraticVoting::get_description_refs(hash), 0);
+		// Clearing returns the deposit, and the closed proposal keeps the hash
+		assert_ok!(QuadraticVoting::clear_description(origin.clone(), hash));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(QuadraticVoting::get_description(hash), None);
+		assert_eq!(QuadraticVoting::get_proposal(0).unwrap().description, Some(hash));
+	});
+}
+
+#[test]
+fn descriptions_are_kept_until_queued_calls_are_done() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000_000_000u128));
+		let description = b"A free kitty for whoever proposed it".to_vec();
+		let hash = BlakeTwo256::hash(&description);
+		assert_ok!(QuadraticVoting::note_description(origin.clone(), description));
+		let mint_call = Call::SubstrateKitties(crypto_kitties::Call::free_create_kitty { recipient: 1 });
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), Some(hash), Some(mint_call.encode())));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"be the kitty".to_vec(), Some(hash), None));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint another".to_vec(), Some(hash), Some(mint_call.encode())));
+		assert_eq!(QuadraticVoting::get_description_refs(hash), 3);
+		for index in 0..3 {
+			assert_ok!(QuadraticVoting::vote(origin.clone(), index, true));
+		}
+		// All three pass. The one without a call is done with its description, the other two wait for enactment
+		run_to_block(62);
+		assert_eq!(QuadraticVoting::get_description_refs(hash), 2);
+		assert_noop!(QuadraticVoting::clear_description(origin.clone(), hash), Error::<Test>::DescriptionInUse);
+		assert_ok!(QuadraticVoting::cancel_enactment(Origin::root(), 0));
+		assert_noop!(QuadraticVoting::clear_description(origin.clone(), hash), Error::<Test>::DescriptionInUse);
+		run_to_block(72);
+		assert_eq!(SubstrateKitties::get_kitties_owned(1).len(), 1);
+		assert_eq!(QuadraticVoting::get_description_refs(hash), 0);
+		assert_ok!(QuadraticVoting::clear_description(origin, hash));
+	});
+}
+
+#[test]
+fn migrates_description_refs() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		let description = b"A free kitty for whoever proposed it".to_vec();
+		let hash = BlakeTwo256::hash(&description);
+		assert_ok!(QuadraticVoting::note_description(origin.clone(), description));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), Some(hash), None));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"be the kitty".to_vec(), Some(hash), None));
+		// Proposals submitted before their references were counted
+		crate::pallet::DescriptionRefs::<Test>::remove(hash);
+		StorageVersion::new(5).put::<QuadraticVoting>();
+		QuadraticVoting::on_runtime_upgrade();
+		assert_eq!(QuadraticVoting::on_chain_storage_version(), 6);
+		assert_eq!(QuadraticVoting::get_description_refs(hash), 2);
 	});
 }
 
@@ -16,12 +104,14 @@ fn can_add_proposal() {
 fn proposal_errors() {
 	ExtBuilder::build().execute_with(|| {
 		let origin = Origin::signed(1);
-		assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"".to_vec()), Error::<Test>::TriedToAddEmptyProposal);
-		assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"ghq".to_vec()), Error::<Test>::NotInVoterSet);
+		assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"".to_vec(), None, None), Error::<Test>::TriedToAddEmptyProposal);
+		assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"ghq".to_vec(), None, None), Error::<Test>::NotInVoterSet);
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
-		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec()));
-		// Change MaxProposals setting in mock.rs to make this work
-		//assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"ghq".to_vec()), Error::<Test>::TooManyProposals); 
+		// MaxProposals is 10 in mock.rs
+		for _ in 0..10 {
+			assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), None, None));
+		}
+		assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"ghq".to_vec(), None, None), Error::<Test>::TooManyProposals);
 	});
 }
 
@@ -32,6 +122,12 @@ fn can_add_voting_power() {
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
 		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 10_000_000u128));
 		assert_eq!(QuadraticVoting::get_reserve(1), 10_000_000u128);
+		// Voting funds are locked, not reserved
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::locks(1)[0].id, VOTING_LOCK_ID);
+		assert_eq!(Balances::locks(1)[0].amount, 10_000_000u128);
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 5_000_000u128));
+		assert_eq!(Balances::locks(1)[0].amount, 15_000_000u128);
 	});
 }
 
@@ -41,7 +137,7 @@ fn add_voting_power_errors() {
 		let origin = Origin::signed(2);
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
 		assert_noop!(QuadraticVoting::reserve_voting_power(origin.clone(), 0u128), Error::<Test>::InvalidReserveAmount);
-		assert_noop!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000_000_000_000u128), pallet_balances::Error::<Test>::InsufficientBalance);
+		assert_noop!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000_000_000_000u128), Error::<Test>::InvalidReserveAmount);
 	});
 }
 
@@ -52,8 +148,146 @@ fn can_release_voting_power() {
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
 		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 10_000_000u128));
 		assert_eq!(QuadraticVoting::get_reserve(1), 10_000_000u128);
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 3_162);
 		assert_ok!(QuadraticVoting::release_all_voting_power(origin.clone()));
 		assert_eq!(QuadraticVoting::get_reserve(1), 0u128);
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 0);
+	});
+}
+
+#[test]
+fn releasing_leaves_other_reserves_alone() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		// Some other pallet's reserve
+		assert_ok!(Balances::reserve(&1, 500));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 10_000_000u128));
+		assert_ok!(QuadraticVoting::release_all_voting_power(origin.clone()));
+		run_to_block(22);
+		assert_ok!(QuadraticVoting::withdraw_unbonded(origin.clone()));
+		assert_eq!(Balances::reserved_balance(1), 500);
+		assert!(Balances::locks(1).is_empty());
+	});
+}
+
+#[test]
+fn released_voting_power_unbonds_before_withdrawal() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 10_000_000u128));
+		assert_noop!(QuadraticVoting::release_voting_power(origin.clone(), 0u128), Error::<Test>::InvalidReleaseAmount);
+		assert_noop!(QuadraticVoting::release_voting_power(origin.clone(), 10_000_001u128), Error::<Test>::InvalidReleaseAmount);
+		// Voting power drops as soon as unbonding starts, but the tokens stay locked
+		assert_ok!(QuadraticVoting::release_voting_power(origin.clone(), 6_000_000u128));
+		System::assert_last_event(Event::QuadraticVoting(crate::Event::VotingPowerUnbonding(1, 6_000_000, 22)));
+		assert_eq!(QuadraticVoting::get_reserve(1), 4_000_000u128);
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 2_000);
+		assert_eq!(Balances::locks(1)[0].amount, 10_000_000u128);
+		assert_noop!(QuadraticVoting::withdraw_unbonded(origin.clone()), Error::<Test>::NothingToWithdraw);
+		run_to_block(12);
+		assert_ok!(QuadraticVoting::release_all_voting_power(origin.clone()));
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 0);
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000u128));
+		assert_ok!(QuadraticVoting::release_voting_power(origin.clone(), 1u128));
+		assert_noop!(QuadraticVoting::release_voting_power(origin.clone(), 1u128), Error::<Test>::TooManyUnbondingChunks);
+		// Only the first release has passed UnbondingPeriod
+		run_to_block(22);
+		assert_ok!(QuadraticVoting::withdraw_unbonded(origin.clone()));
+		System::assert_last_event(Event::QuadraticVoting(crate::Event::UnbondedWithdrawn(1, 6_000_000)));
+		assert_eq!(Balances::locks(1)[0].amount, 4_000_000u128 + 1_000_000u128);
+		assert_eq!(QuadraticVoting::get_unbonding(1).len(), 2);
+	});
+}
+
+#[test]
+fn migrates_reserves_to_locks() {
+	ExtBuilder::build().execute_with(|| {
+		// Storage as left by the reserve based version of the pallet
+		StorageVersion::new(0).put::<QuadraticVoting>();
+		assert_ok!(Balances::reserve(&2, 1_000_000));
+		crate::pallet::ReserveSet::<Test>::insert(2, 1_000_000u128);
+		assert_ok!(Balances::reserve(&3, 40_000));
+		crate::pallet::ReserveSet::<Test>::insert(3, 40_000u128);
+		// 99 has no system account to find them by, so is skipped
+		crate::pallet::ReserveSet::<Test>::insert(99, 500u128);
+		crate::migrations::v1::migrate::<Test>();
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::locks(2)[0].id, VOTING_LOCK_ID);
+		assert_eq!(Balances::locks(2)[0].amount, 1_000_000u128);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::locks(3)[0].amount, 40_000u128);
+		// Accounts without a reserve are left alone
+		assert!(Balances::locks(1).is_empty());
+		assert!(Balances::locks(99).is_empty());
+		assert_eq!(QuadraticVoting::get_reserve(99), 500);
+		assert_eq!(QuadraticVoting::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn migrates_proposal_queue_into_referenda() {
+	ExtBuilder::build().execute_with(|| {
+		// The version 0 queue: texts and proposers in two values, and the tally of the head of the queue
+		StorageVersion::new(1).put::<QuadraticVoting>();
+		System::set_block_number(75);
+		fn put<V: Encode>(item: &[u8], value: V) {
+			frame_support::storage::unhashed::put(&frame_support::storage::storage_prefix(b"QuadraticVoting", item), &value);
+		}
+		put(b"Proposals", vec![b"mint a kitty".to_vec(), b"mint another kitty".to_vec()]);
+		put(b"Proposers", vec![1u64, 2u64]);
+		put(b"Tally", (5u128, 3u128));
+		crate::migrations::v2::migrate::<Test>();
+		let proposal = QuadraticVoting::get_proposal(0).unwrap();
+		assert_eq!((proposal.proposer, proposal.title.into_inner(), proposal.submitted), (1, b"mint a kitty".to_vec(), 75));
+		assert_eq!((proposal.description, proposal.call, proposal.status), (None, None, ProposalStatus::Voting));
+		assert_eq!(QuadraticVoting::get_proposal(1).unwrap().proposer, 2);
+		// The head keeps its tally and closes at the next boundary, the next one a voting window later
+		let head = QuadraticVoting::get_referendum(0).unwrap();
+		assert_eq!((head.deposit, head.start, head.end, head.tally, head.voters), (0, 75, 120, (5, 3), 0));
+		let next = QuadraticVoting::get_referendum(1).unwrap();
+		assert_eq!((next.end, next.tally), (180, (0, 0)));
+		assert_eq!(QuadraticVoting::get_open_referenda().into_inner(), vec![(0, 120), (1, 180)]);
+		assert_eq!(QuadraticVoting::get_referendum_count(), 2);
+		// The old values are gone
+		for item in [&b"Proposals"[..], b"Proposers", b"Tally"] {
+			assert!(!frame_support::storage::unhashed::exists(&frame_support::storage::storage_prefix(b"QuadraticVoting", item)));
+		}
+		assert_eq!(QuadraticVoting::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn migrates_total_voting_power() {
+	ExtBuilder::build().execute_with(|| {
+		// Reserves made before TotalVotingPower was kept
+		StorageVersion::new(2).put::<QuadraticVoting>();
+		crate::pallet::ReserveSet::<Test>::insert(2, 1_000_000u128);
+		crate::pallet::ReserveSet::<Test>::insert(3, 40_000u128);
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 0);
+		QuadraticVoting::on_runtime_upgrade();
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 1_200);
+		assert_eq!(QuadraticVoting::get_total_credit_votes(), 1_200);
+		assert_eq!(QuadraticVoting::on_chain_storage_version(), 6);
+		// Running it again changes nothing
+		crate::pallet::ReserveSet::<Test>::insert(4, 10_000u128);
+		QuadraticVoting::on_runtime_upgrade();
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 1_200);
+		assert_eq!(QuadraticVoting::get_total_credit_votes(), 1_200);
+	});
+}
+
+#[test]
+fn migrates_commitments() {
+	ExtBuilder::build().execute_with(|| {
+		// A commitment made before they recorded the reserve behind them
+		StorageVersion::new(3).put::<QuadraticVoting>();
+		crate::pallet::ReserveSet::<Test>::insert(2, 1_000_000u128);
+		let hash = BlakeTwo256::hash_of(&(2u64, true, [2u8; 32]));
+		frame_support::storage::unhashed::put(&crate::pallet::Commitments::<Test>::hashed_key_for(0, 2), &(hash, 1_000u128));
+		QuadraticVoting::on_runtime_upgrade();
+		assert_eq!(QuadraticVoting::on_chain_storage_version(), 6);
+		let commitment = QuadraticVoting::get_commitment(0, 2).unwrap();
+		assert_eq!((commitment.hash, commitment.power, commitment.reserve), (hash, 1_000, 1_000_000));
 	});
 }
 
@@ -65,6 +299,32 @@ fn release_voting_power_errors() {
 	});
 }
 
+#[test]
+fn voting_power_locked_until_referendum_finalized() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(1)));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(2)));
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(1), 1_000_000u128));
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(2), 1_000_000u128));
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(2), 1));
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"mint a kitty".to_vec(), None, None));
+		run_to_block(10);
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"mint another kitty".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::vote(Origin::signed(1), 1, true));
+		assert_ok!(QuadraticVoting::vote(Origin::signed(1), 0, true));
+		// The lock runs to the end of the later referendum, and covers the delegator as well
+		assert_eq!(QuadraticVoting::get_vote_lock(1), Some(70));
+		assert_eq!(QuadraticVoting::get_vote_lock(2), Some(70));
+		assert_noop!(QuadraticVoting::release_all_voting_power(Origin::signed(1)), Error::<Test>::VotingPowerLocked);
+		run_to_block(62);
+		assert_noop!(QuadraticVoting::release_all_voting_power(Origin::signed(2)), Error::<Test>::VotingPowerLocked);
+		run_to_block(70);
+		assert_ok!(QuadraticVoting::release_all_voting_power(Origin::signed(1)));
+		assert_ok!(QuadraticVoting::release_all_voting_power(Origin::signed(2)));
+		assert_eq!(QuadraticVoting::get_vote_lock(1), None);
+	});
+}
+
 #[test]
 fn can_vote() {
 	ExtBuilder::build().execute_with(|| {
@@ -74,12 +334,68 @@ fn can_vote() {
 		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000u128));
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
 		assert_ok!(QuadraticVoting::reserve_voting_power(origin2.clone(), 1_000_000u128));
-		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec()));
-		assert_ok!(QuadraticVoting::vote_on_current_proposal(origin.clone(), true));
-		assert_ok!(QuadraticVoting::vote_on_current_proposal(origin2.clone(), false));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 0, true));
+		assert_ok!(QuadraticVoting::vote(origin2.clone(), 0, false));
 		run_to_block(10);
-		assert_eq!(QuadraticVoting::get_tally().0, 1_000);
-		assert_eq!(QuadraticVoting::get_tally().1, 1_000);
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (1_000, 1_000));
+	});
+}
+
+#[test]
+fn revoting_replaces_earlier_ballot() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000u128));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 0, true));
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 0, true));
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (1_000, 0));
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 0, false));
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (0, 1_000));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::VoteChanged(1, 0, false, 1_000)));
+		assert_eq!(QuadraticVoting::get_vote(0, 1), Some(VoteRecord { aye: false, power: 1_000, via: None }));
+		// Ledger is cleared once the referendum is finalized
+		run_to_block(62);
+		assert_eq!(QuadraticVoting::get_vote(0, 1), None);
+	});
+}
+
+#[test]
+fn ballots_are_capped_and_leftovers_cleared_on_idle() {
+	let mut ext = ExtBuilder::build();
+	ext.execute_with(|| {
+		MaxVoters::set(&3);
+		for who in 1..=4u64 {
+			assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(who)));
+			assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(who), 1_000_000u128));
+		}
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"mint a kitty".to_vec(), None, None));
+		for who in 1..=3u64 {
+			assert_ok!(QuadraticVoting::vote(Origin::signed(who), 0, true));
+		}
+		assert_noop!(QuadraticVoting::vote(Origin::signed(4), 0, true), Error::<Test>::TooManyVoters);
+		// Changing a ballot takes no more room
+		assert_ok!(QuadraticVoting::vote(Origin::signed(1), 0, false));
+	});
+	// Ballots already in the backend are what clearing is limited on
+	ext.commit_all().unwrap();
+	ext.execute_with(|| {
+		// A cap lowered after the votes were cast leaves ballots over for on_idle
+		MaxVoters::set(&1);
+		run_to_block(62);
+		assert!(QuadraticVoting::get_archived_proposal(0).is_some());
+		assert!(crate::pallet::StaleBallots::<Test>::contains_key(0));
+		assert_eq!(crate::pallet::Votes::<Test>::iter_prefix(0).count(), 2);
+		// Nothing is cleared without the weight for it
+		QuadraticVoting::on_idle(62, 0);
+		assert!(crate::pallet::StaleBallots::<Test>::contains_key(0));
+		QuadraticVoting::on_idle(62, u64::MAX);
+		assert!(!crate::pallet::StaleBallots::<Test>::contains_key(0));
+		for who in 1..=3u64 {
+			assert_eq!(QuadraticVoting::get_vote(0, who), None);
+		}
 	});
 }
 
@@ -90,10 +406,317 @@ fn vote_errors() {
 		let origin2 = Origin::signed(2);
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
 		assert_ok!(QuadraticVoting::reserve_voting_power(origin2.clone(), 1_000_000u128));
-		assert_noop!(QuadraticVoting::vote_on_current_proposal(origin2.clone(), true), Error::<Test>::NotInVoterSet);
-		assert_noop!(QuadraticVoting::vote_on_current_proposal(origin.clone(), true), Error::<Test>::VotedWithNoVotingPower);
+		assert_noop!(QuadraticVoting::vote(origin2.clone(), 0, true), Error::<Test>::NotInVoterSet);
+		assert_noop!(QuadraticVoting::vote(origin.clone(), 0, true), Error::<Test>::VotedWithNoVotingPower);
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
-		assert_noop!(QuadraticVoting::vote_on_current_proposal(origin2.clone(), true), Error::<Test>::NoProposalToVoteFor);
+		assert_noop!(QuadraticVoting::vote(origin2.clone(), 0, true), Error::<Test>::NoProposalToVoteFor);
+	});
+}
+
+#[test]
+fn delegated_power_counts_for_delegate() {
+	ExtBuilder::build().execute_with(|| {
+		for who in 1..=4u64 {
+			assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(who)));
+			assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(who), 1_000_000u128));
+		}
+		// 3 delegates to 2, who delegates to 1
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(3), 2));
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(2), 1));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::Delegated(2, 1)));
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"mint a kitty".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::vote(Origin::signed(1), 0, true));
+		let referendum = QuadraticVoting::get_referendum(0).unwrap();
+		assert_eq!(referendum.tally, (3_000, 0));
+		assert_eq!(referendum.voters, 3);
+		assert_eq!(QuadraticVoting::get_vote(0, 3), Some(VoteRecord { aye: true, power: 1_000, via: Some(2) }));
+		// 2 overrides their delegate on this proposal, and 3 follows 2
+		assert_ok!(QuadraticVoting::vote(Origin::signed(2), 0, false));
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (1_000, 2_000));
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().voters, 3);
+		// A later vote by the delegate leaves the override alone
+		assert_ok!(QuadraticVoting::vote(Origin::signed(1), 0, true));
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (1_000, 2_000));
+		// Undelegated power is no longer counted on new referenda
+		assert_ok!(QuadraticVoting::undelegate(Origin::signed(2)));
+		assert_eq!(QuadraticVoting::get_delegate(2), None);
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"mint another kitty".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::vote(Origin::signed(1), 1, true));
+		assert_eq!(QuadraticVoting::get_referendum(1).unwrap().tally, (1_000, 0));
+	});
+}
+
+#[test]
+fn delegation_errors() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(QuadraticVoting::delegate(Origin::signed(1), 1), Error::<Test>::DelegatedToSelf);
+		assert_noop!(QuadraticVoting::delegate(Origin::signed(1), 2), Error::<Test>::NotInVoterSet);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(1)));
+		assert_noop!(QuadraticVoting::delegate(Origin::signed(1), 2), Error::<Test>::DelegateNotInVoterSet);
+		assert_noop!(QuadraticVoting::undelegate(Origin::signed(1)), Error::<Test>::NotDelegating);
+		for who in 2..=7u64 {
+			assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(who)));
+		}
+		// 1 -> 2 -> 3 -> 4 -> 5 -> 6 is as long as a chain may get
+		for who in 1..=5u64 {
+			assert_ok!(QuadraticVoting::delegate(Origin::signed(who), who + 1));
+		}
+		assert_noop!(QuadraticVoting::delegate(Origin::signed(6), 1), Error::<Test>::DelegationLoop);
+		assert_noop!(QuadraticVoting::delegate(Origin::signed(7), 1), Error::<Test>::DelegationChainTooLong);
+		// Everyone up the chain counts everyone below them
+		assert_eq!((2..=6u64).map(QuadraticVoting::get_delegated_count).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+		// Redelegating replaces the earlier delegation
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(1), 7));
+		assert_eq!(QuadraticVoting::get_delegate(1), Some(7));
+		assert!(QuadraticVoting::get_delegators(2).is_empty());
+		assert_eq!((2..=7u64).map(QuadraticVoting::get_delegated_count).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 1]);
+		assert_ok!(QuadraticVoting::undelegate(Origin::signed(4)));
+		assert_eq!((4..=6u64).map(QuadraticVoting::get_delegated_count).collect::<Vec<_>>(), vec![2, 0, 1]);
+	});
+}
+
+#[test]
+fn delegations_are_cleared_when_members_leave() {
+	ExtBuilder::build().execute_with(|| {
+		for who in 1..=4u64 {
+			assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(who)));
+		}
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(2), 1));
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(3), 1));
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(1), 4));
+		// The delegate leaves, taking the delegations to and from them along
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 1, false, 0, 10));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::DelegationsCleared(1)));
+		assert_eq!(QuadraticVoting::get_delegate(2), None);
+		assert_eq!(QuadraticVoting::get_delegate(3), None);
+		assert!(QuadraticVoting::get_delegators(1).is_empty());
+		assert!(QuadraticVoting::get_delegators(4).is_empty());
+		// A delegator leaving drops out of their delegate's list
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(2), 4));
+		assert_ok!(IdentityPallet::remove_member(Origin::root(), 2, false, 0, 10));
+		assert!(QuadraticVoting::get_delegators(4).is_empty());
+	});
+}
+
+#[test]
+fn can_withdraw_and_veto_proposals() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(2)));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000u128));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint another kitty".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 0, true));
+		assert_noop!(QuadraticVoting::withdraw_proposal(Origin::signed(2), 0, 1), Error::<Test>::NotProposer);
+		assert_noop!(QuadraticVoting::withdraw_proposal(origin.clone(), 0, 0), Error::<Test>::VoterWitnessTooLow);
+		assert_ok!(QuadraticVoting::withdraw_proposal(origin.clone(), 0, 1));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::ProposalWithdrawn(0, 1)));
+		assert_eq!(QuadraticVoting::get_referendum(0), None);
+		assert_eq!(QuadraticVoting::get_vote(0, 1), None);
+		assert_eq!(QuadraticVoting::get_open_referenda().len(), 1);
+		assert_noop!(QuadraticVoting::withdraw_proposal(origin.clone(), 0, 1), Error::<Test>::NoProposalToVoteFor);
+		assert_noop!(QuadraticVoting::veto_proposal(origin.clone(), 1, 0), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(QuadraticVoting::veto_proposal(Origin::root(), 1, 0));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::ProposalVetoed(1)));
+		assert!(QuadraticVoting::get_open_referenda().is_empty());
+		assert_noop!(QuadraticVoting::veto_proposal(Origin::root(), 1, 0), Error::<Test>::NoProposalToVoteFor);
+	});
+}
+
+#[test]
+fn proposal_deposits_are_refunded_or_slashed() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(2);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(3)));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(7)));
+		assert_noop!(QuadraticVoting::add_proposal(Origin::signed(7), b"mint a kitty".to_vec(), None, None), Error::<Test>::InsufficientProposalDeposit);
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000u128));
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(3), 4_000_000u128));
+		for _ in 0..4 {
+			assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), None, None));
+		}
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().deposit, 1_000);
+		assert_eq!(Balances::reserved_balance(2), 4_000);
+		// Withdrawn proposals are refunded, vetoed ones slashed
+		assert_ok!(QuadraticVoting::withdraw_proposal(origin.clone(), 0, 0));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::DepositRefunded(0, 2, 1_000)));
+		assert_ok!(QuadraticVoting::veto_proposal(Origin::root(), 1, 0));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::DepositSlashed(1, 2, 1_000)));
+		assert_eq!(Balances::reserved_balance(2), 2_000);
+		assert_eq!(Balances::free_balance(2), 1_000_000_000_000 - 3_000);
+		// Rejected with a third of the turnout in ayes is refunded, with no ayes slashed
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 2, true));
+		assert_ok!(QuadraticVoting::vote(Origin::signed(3), 2, false));
+		assert_ok!(QuadraticVoting::vote(Origin::signed(3), 3, false));
+		run_to_block(62);
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::DepositRefunded(2, 2, 1_000)));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::DepositSlashed(3, 2, 1_000)));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 1_000_000_000_000 - 2_000);
+	});
+}
+
+#[test]
+fn runtime_api_queries() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(2)));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000_000u128));
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(2), 1_000_000u128));
+		assert_ok!(QuadraticVoting::delegate(Origin::signed(2), 1));
+		assert_eq!(QuadraticVoting::voting_power_of(1), VotingPowerInfo { own: 31_622, delegated: 1_000 });
+		// Powers go over RPC as strings, so JavaScript clients don't round them
+		let power = VotingPowerInfo { own: u128::MAX, delegated: 1_000 };
+		let json = serde_json::to_string(&power).unwrap();
+		assert_eq!(json, r#"{"own":"340282366920938463463374607431768211455","delegated":"1000"}"#);
+		assert_eq!(serde_json::from_str::<VotingPowerInfo>(&json).unwrap(), power);
+		assert_eq!(QuadraticVoting::blocks_until_next_finalization(), None);
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), None, None));
+		run_to_block(12);
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 0, true));
+		assert_eq!(QuadraticVoting::blocks_until_next_finalization(), Some(50));
+		let open = QuadraticVoting::open_referenda_status();
+		assert_eq!(open.len(), 1);
+		assert_eq!((open[0].index, open[0].proposer, open[0].ayes, open[0].voters), (0, 1, 32_622, 2));
+		assert_eq!(open[0].text, b"mint a kitty".to_vec());
+		assert!(serde_json::to_string(&open[0]).unwrap().contains(r#""ayes":"32622""#));
+		assert!(open[0].projected_pass);
+		assert_eq!(QuadraticVoting::projected_outcome(0), Some(true));
+		assert_eq!(QuadraticVoting::projected_outcome(1), None);
+	});
+}
+
+#[test]
+fn secret_ballots_are_committed_then_revealed() {
+	ExtBuilder::build().execute_with(|| {
+		SecretBallots::set(&true);
+		for who in 1..=3u64 {
+			assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(who)));
+		}
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(1), 1_000_000u128));
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(2), 4_000_000u128));
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(3), 1_000_000u128));
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"mint a kitty".to_vec(), None, None));
+		assert_noop!(QuadraticVoting::vote(Origin::signed(1), 0, true), Error::<Test>::SecretBallotRequired);
+		// Commit phase runs for the first half of the voting window, and keeps the tally hidden
+		assert_ok!(QuadraticVoting::commit_vote(Origin::signed(1), 0, BlakeTwo256::hash_of(&(1u64, true, [1u8; 32]))));
+		assert_ok!(QuadraticVoting::commit_vote(Origin::signed(2), 0, BlakeTwo256::hash_of(&(2u64, false, [2u8; 32]))));
+		assert_ok!(QuadraticVoting::commit_vote(Origin::signed(3), 0, BlakeTwo256::hash_of(&(3u64, true, [3u8; 32]))));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::VoteCommitted(3, 0)));
+		assert_noop!(QuadraticVoting::reveal_vote(Origin::signed(1), 0, true, [1u8; 32]), Error::<Test>::NotInRevealPhase);
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (0, 0));
+		// Reveal phase runs for the second half
+		run_to_block(32);
+		assert_noop!(QuadraticVoting::commit_vote(Origin::signed(1), 0, BlakeTwo256::hash_of(&(1u64, false, [1u8; 32]))), Error::<Test>::NotInCommitPhase);
+		assert_noop!(QuadraticVoting::reveal_vote(Origin::signed(1), 0, false, [1u8; 32]), Error::<Test>::CommitmentMismatch);
+		assert_ok!(QuadraticVoting::reveal_vote(Origin::signed(1), 0, true, [1u8; 32]));
+		assert_ok!(QuadraticVoting::reveal_vote(Origin::signed(2), 0, false, [2u8; 32]));
+		assert_noop!(QuadraticVoting::reveal_vote(Origin::signed(2), 0, false, [2u8; 32]), Error::<Test>::NoCommitment);
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (1_000, 2_000));
+		// 3 never revealed, so a tenth of their voting funds is slashed
+		run_to_block(62);
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::UnrevealedVotePenalized(3, 0, 100_000)));
+		assert_eq!(QuadraticVoting::get_reserve(3), 900_000);
+		assert_eq!(Balances::free_balance(3), 1_000_000_000_000 - 100_000);
+		assert_eq!(QuadraticVoting::get_commitment(0, 3), None);
+		// Referenda submitted with secret ballots switched off take open votes
+		SecretBallots::set(&false);
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"mint a kitty".to_vec(), None, None));
+		assert_noop!(QuadraticVoting::commit_vote(Origin::signed(1), 1, BlakeTwo256::hash_of(&(1u64, true, [1u8; 32]))), Error::<Test>::NotSecretReferendum);
+	});
+}
+
+#[test]
+fn unrevealed_penalty_only_takes_committed_voting_funds() {
+	ExtBuilder::build().execute_with(|| {
+		SecretBallots::set(&true);
+		for who in 1..=4u64 {
+			assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(who)));
+		}
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"mint a kitty".to_vec(), None, None));
+		// 3 commits with a reserve of 1_000_000 and adds to it afterwards
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(3), 1_000_000u128));
+		assert_ok!(QuadraticVoting::commit_vote(Origin::signed(3), 0, BlakeTwo256::hash_of(&(3u64, true, [3u8; 32]))));
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(3), 3_000_000u128));
+		// All of 4's balance backs their voting power, and most of it then goes into a proposal deposit
+		Balances::make_free_balance_be(&4, 1_000_000);
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(4), 1_000_000u128));
+		assert_ok!(QuadraticVoting::commit_vote(Origin::signed(4), 0, BlakeTwo256::hash_of(&(4u64, true, [4u8; 32]))));
+		run_to_block(10);
+		ProposalDeposit::set(&950_000);
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(4), b"mint a puppy".to_vec(), None, None));
+		assert_eq!((Balances::free_balance(4), Balances::reserved_balance(4)), (50_000, 950_000));
+		run_to_block(62);
+		// Neither reveals. 3 loses a tenth of what they committed, not of their reserve now
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::UnrevealedVotePenalized(3, 0, 100_000)));
+		assert_eq!(QuadraticVoting::get_reserve(3), 3_900_000);
+		assert_eq!(Balances::free_balance(3), 1_000_000_000_000 - 100_000);
+		// 4 only has 50_000 of voting funds outside the deposit, so that's all they lose
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::UnrevealedVotePenalized(4, 0, 50_000)));
+		assert_eq!(QuadraticVoting::get_reserve(4), 950_000);
+		assert_eq!((Balances::free_balance(4), Balances::reserved_balance(4)), (0, 950_000));
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 1_974 + 974);
+	});
+}
+
+#[test]
+fn voice_credits_are_spent_across_referenda() {
+	ExtBuilder::build().execute_with(|| {
+		Mode::set(&VotingMode::VoiceCredits);
+		for who in 1..=2u64 {
+			assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(who)));
+		}
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"first".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"second".to_vec(), None, None));
+		assert_noop!(QuadraticVoting::vote(Origin::signed(1), 0, true), Error::<Test>::WrongVotingMode);
+		assert_noop!(QuadraticVoting::vote_with_credits(Origin::signed(3), 0, true, 1), Error::<Test>::NotInVoterSet);
+		assert_noop!(QuadraticVoting::vote_with_credits(Origin::signed(1), 0, true, 0), Error::<Test>::NoVotesCast);
+		// 6 votes cost 36 credits and 8 votes cost 64, using up the budget of 100
+		assert_ok!(QuadraticVoting::vote_with_credits(Origin::signed(1), 0, true, 6));
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::VoiceCreditsSpent(1, 0, 6, 64)));
+		assert_ok!(QuadraticVoting::vote_with_credits(Origin::signed(1), 1, false, 8));
+		assert_noop!(QuadraticVoting::vote_with_credits(Origin::signed(2), 0, true, 11), Error::<Test>::NotEnoughVoiceCredits);
+		assert_noop!(QuadraticVoting::vote_with_credits(Origin::signed(1), 0, true, 7), Error::<Test>::NotEnoughVoiceCredits);
+		// Changing a ballot gives back what it cost
+		assert_ok!(QuadraticVoting::vote_with_credits(Origin::signed(1), 0, false, 5));
+		assert_eq!(QuadraticVoting::get_credits_spent(1), Some((0, 89)));
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (0, 5));
+		assert_eq!(QuadraticVoting::get_referendum(1).unwrap().tally, (0, 8));
+		// Unspent credits expire, and the next round starts with a full budget
+		run_to_block(70);
+		assert_eq!(QuadraticVoting::get_credit_ballot(0, 1), None);
+		assert_ok!(QuadraticVoting::add_proposal(Origin::signed(1), b"third".to_vec(), None, None));
+		assert_noop!(QuadraticVoting::vote_with_credits(Origin::signed(1), 2, true, 4), Error::<Test>::NotEnoughVoiceCredits);
+		run_to_block(100);
+		assert_ok!(QuadraticVoting::vote_with_credits(Origin::signed(1), 2, true, 10));
+		assert_eq!(QuadraticVoting::get_credits_spent(1), Some((1, 100)));
+	});
+}
+
+#[test]
+fn electorate_follows_voting_mode() {
+	ExtBuilder::build().execute_with(|| {
+		PowerCurve::set(&Curve::Linear);
+		for who in 1..=3u64 {
+			assert_ok!(IdentityPallet::try_add_as_social_graph_originator(Origin::signed(who)));
+		}
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(1), 1_000_000u128));
+		assert_ok!(QuadraticVoting::reserve_voting_power(Origin::signed(2), 40_000u128));
+		// Weighed by reserve, the electorate is the voting power reserved
+		assert_eq!(QuadraticVoting::electorate(), 1_040_000);
+		// With voice credits it's the votes each member could put behind one referendum: 10 each out of 100 credits
+		Mode::set(&VotingMode::VoiceCredits);
+		assert_eq!(QuadraticVoting::electorate(), 30);
+		// or 1_000 and 200 out of budgets the size of their reserves
+		VoiceCredits::set(&crate::CreditSource::Reserve);
+		assert_eq!(QuadraticVoting::electorate(), 1_200);
+		assert_ok!(QuadraticVoting::release_all_voting_power(Origin::signed(2)));
+		assert_eq!(QuadraticVoting::electorate(), 1_000);
+		Mode::set(&VotingMode::SqrtOfReserve);
+		assert_eq!(QuadraticVoting::electorate(), 1_000_000);
 	});
 }
 
@@ -106,18 +729,210 @@ fn finalize_kitty_vote() {
 		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
 		assert_ok!(QuadraticVoting::reserve_voting_power(origin1.clone(), 1_000_000_000_000_000u128));
 		assert_ok!(QuadraticVoting::reserve_voting_power(origin2.clone(), 1_000_000u128));
-		assert_ok!(QuadraticVoting::add_proposal(origin1.clone(), b"be the kitty".to_vec()));
-		assert_ok!(QuadraticVoting::add_proposal(origin1.clone(), b"mint a kitty".to_vec()));
-		// Check finalizing failed proposal
-		assert_ok!(QuadraticVoting::vote_on_current_proposal(origin1.clone(), false));
-		assert_ok!(QuadraticVoting::vote_on_current_proposal(origin2.clone(), true));
-		run_to_block(61);
-		assert_eq!(QuadraticVoting::get_proposals()[0], b"mint a kitty".to_vec());
-		assert_ok!(QuadraticVoting::vote_on_current_proposal(origin1.clone(), true));
-		assert_ok!(QuadraticVoting::vote_on_current_proposal(origin2.clone(), false));
-		run_to_block(121);
+		assert_ok!(QuadraticVoting::add_proposal(origin1.clone(), b"be the kitty".to_vec(), None, None));
+		let mint_call = Call::SubstrateKitties(crypto_kitties::Call::free_create_kitty { recipient: 1 });
+		assert_ok!(QuadraticVoting::add_proposal(origin1.clone(), b"mint a kitty".to_vec(), None, Some(mint_call.encode())));
+		// Both referenda are open at once. The first fails and the second passes
+		assert_ok!(QuadraticVoting::vote(origin1.clone(), 0, false));
+		assert_ok!(QuadraticVoting::vote(origin2.clone(), 0, true));
+		assert_ok!(QuadraticVoting::vote(origin1.clone(), 1, true));
+		assert_ok!(QuadraticVoting::vote(origin2.clone(), 1, false));
+		run_to_block(62);
+		assert_eq!(QuadraticVoting::get_open_referenda().len(), 0);
+		assert_eq!(QuadraticVoting::get_referendum(1), None);
+		// The passed call waits out the enactment period before it is dispatched
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::EnactmentScheduled(1, 72)));
+		assert_eq!(QuadraticVoting::get_enactment_block(1), Some(72));
+		assert_eq!(SubstrateKitties::get_kitties_owned(1).len(), 0);
+		assert_eq!(QuadraticVoting::get_archived_proposal(1).unwrap().execution, None);
+		run_to_block(72);
 		// Get kitties owned by origin1
-		assert_eq!(QuadraticVoting::get_proposals().len(), 0);
 		assert_eq!(SubstrateKitties::get_kitties_owned(1).len(), 1);
+		assert_eq!(QuadraticVoting::get_enactment_block(1), None);
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::ProposalExecuted { proposal: 1, result: Ok(()) }));
+		// Both outcomes are kept in the archive
+		let archived = QuadraticVoting::get_archived_proposal(1).unwrap();
+		assert_eq!(archived.proposer, 1);
+		assert_eq!(archived.call_hash, Some(BlakeTwo256::hash(&mint_call.encode())));
+		assert_eq!(archived.outcome, ProposalOutcome::Passed);
+		assert_eq!(QuadraticVoting::get_proposal(1).unwrap().status, ProposalStatus::Passed);
+		assert_eq!(archived.execution, Some(ExecutionOutcome::Succeeded));
+		assert_eq!(archived.finalized_at, 62);
+		assert_eq!(QuadraticVoting::get_archived_proposal(0).unwrap().outcome, ProposalOutcome::Rejected);
+		assert_eq!(QuadraticVoting::get_archived_proposal(0).unwrap().execution, None);
+	});
+}
+
+#[test]
+fn failed_proposal_calls_are_reported_not_panicked() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000u128));
+		// The proposer already owns MaxKittiesOwned kitties, so minting another one fails
+		assert_ok!(SubstrateKitties::free_create_kitty(Origin::root(), 1));
+		let mint_call = Call::SubstrateKitties(crypto_kitties::Call::free_create_kitty { recipient: 1 });
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), None, Some(mint_call.encode())));
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 0, true));
+		run_to_block(72);
+		System::assert_has_event(Event::QuadraticVoting(crate::Event::ProposalExecutionFailed {
+			proposal: 0,
+			error: crypto_kitties::Error::<Test>::TooManyOwned.into(),
+		}));
+		assert_eq!(SubstrateKitties::get_kitties_owned(1).len(), 1);
+		assert_eq!(QuadraticVoting::get_archived_proposal(0).unwrap().execution, Some(ExecutionOutcome::Failed));
+		assert_eq!(QuadraticVoting::get_proposal(0).unwrap().status, ProposalStatus::Passed);
+		// Blocks keep being produced
+		run_to_block(73);
+		assert_eq!(System::block_number(), 73);
+	});
+}
+
+#[test]
+fn scheduled_enactments_can_be_cancelled() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000u128));
+		let mint_call = Call::SubstrateKitties(crypto_kitties::Call::free_create_kitty { recipient: 1 });
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"mint a kitty".to_vec(), None, Some(mint_call.encode())));
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 0, true));
+		assert_noop!(QuadraticVoting::cancel_enactment(Origin::root(), 0), Error::<Test>::NotScheduled);
+		run_to_block(62);
+		assert_noop!(QuadraticVoting::cancel_enactment(origin.clone(), 0), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(QuadraticVoting::cancel_enactment(Origin::root(), 0));
+		System::assert_last_event(Event::QuadraticVoting(crate::Event::EnactmentCancelled(0)));
+		assert_eq!(QuadraticVoting::get_enactment_block(0), None);
+		assert_eq!(QuadraticVoting::get_proposal(0).unwrap().status, ProposalStatus::Cancelled);
+		assert_eq!(QuadraticVoting::get_archived_proposal(0).unwrap().execution, Some(ExecutionOutcome::Cancelled));
+		run_to_block(72);
+		assert_eq!(SubstrateKitties::get_kitties_owned(1).len(), 0);
+	});
+}
+
+#[test]
+fn archive_is_pruned_after_retention() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin.clone(), 1_000_000u128));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"first".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"second".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::vote(origin.clone(), 1, true));
+		assert_ok!(QuadraticVoting::withdraw_proposal(origin.clone(), 0, 0));
+		let archived = QuadraticVoting::get_archived_proposal(0).unwrap();
+		assert_eq!((archived.outcome, archived.finalized_at, archived.call_hash), (ProposalOutcome::Withdrawn, 2, None));
+		assert_eq!(QuadraticVoting::get_proposal(0).unwrap().status, ProposalStatus::Withdrawn);
+		run_to_block(62);
+		let archived = QuadraticVoting::get_archived_proposal(1).unwrap();
+		assert_eq!((archived.tally, archived.turnout(), archived.voters), ((1_000, 0), 1_000, 1));
+		// Each record is kept for ArchiveRetention blocks after it was closed
+		run_to_block(101);
+		assert!(QuadraticVoting::get_archived_proposal(0).is_some());
+		run_to_block(102);
+		assert_eq!(QuadraticVoting::get_archived_proposal(0), None);
+		assert_eq!(QuadraticVoting::get_proposal(0), None);
+		assert!(QuadraticVoting::get_archived_proposal(1).is_some());
+		run_to_block(162);
+		assert_eq!(QuadraticVoting::get_archived_proposal(1), None);
+	});
+}
+
+#[test]
+fn referenda_close_at_their_own_window_end() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"first".to_vec(), None, None));
+		run_to_block(30);
+		assert_ok!(QuadraticVoting::add_proposal(origin.clone(), b"second".to_vec(), None, None));
+		assert_eq!(QuadraticVoting::get_open_referenda().to_vec(), vec![(0, 62), (1, 90)]);
+		run_to_block(62);
+		assert_eq!(QuadraticVoting::get_referendum(0), None);
+		assert!(QuadraticVoting::get_referendum(1).is_some());
+		run_to_block(90);
+		assert_eq!(QuadraticVoting::get_referendum(1), None);
+		assert_eq!(QuadraticVoting::get_open_referenda().len(), 0);
+	});
+}
+
+parameter_types! {
+	pub Half: Perbill = Perbill::from_percent(50);
+}
+
+#[test]
+fn approval_and_quorum_rules() {
+	let summary = |ayes, nays, voters, electorate, members| TallySummary { ayes, nays, voters, electorate, members };
+	// 60% aye is a simple majority but not a two thirds supermajority
+	assert!(SimpleMajority::approved(&summary(600, 400, 2, 10_000, 10)));
+	assert!(!SuperMajority::<TwoThirds>::approved(&summary(600, 400, 2, 10_000, 10)));
+	assert!(SuperMajority::<TwoThirds>::approved(&summary(700, 300, 2, 10_000, 10)));
+	// A quarter of the electorate turning out means positive bias needs twice as many ayes as nays
+	assert!(!PositiveTurnoutBias::approved(&summary(1_600, 900, 2, 10_000, 10)));
+	assert!(PositiveTurnoutBias::approved(&summary(1_800, 700, 2, 10_000, 10)));
+	// With the whole electorate voting it is a simple majority
+	assert!(PositiveTurnoutBias::approved(&summary(5_100, 4_900, 2, 10_000, 10)));
+	// Negative bias lets a low turnout pass even with more nays
+	assert!(NegativeTurnoutBias::approved(&summary(1_000, 1_500, 2, 10_000, 10)));
+	assert!(!NegativeTurnoutBias::approved(&summary(4_900, 5_100, 2, 10_000, 10)));
+	// Quorum as raw voting power or as a share of the voter set
+	assert!(AbsolutePower::<ConstU128<10_000>>::quorum_reached(&summary(10_001, 0, 1, 10_001, 10)));
+	assert!(!AbsolutePower::<ConstU128<10_000>>::quorum_reached(&summary(10_000, 0, 1, 10_000, 10)));
+	assert!(MemberTurnout::<Half>::quorum_reached(&summary(1, 1, 5, 10_000, 10)));
+	assert!(!MemberTurnout::<Half>::quorum_reached(&summary(1, 1, 4, 10_000, 10)));
+	assert!(!MemberTurnout::<Half>::quorum_reached(&summary(1, 1, 5, 10_000, 11)));
+}
+
+#[test]
+fn voting_power_follows_the_configured_curve() {
+	assert_eq!((SqrtCurve::power(1_000_000), SqrtCurve::max_power(1_000)), (1_000, None));
+	assert_eq!(LinearCurve::power(1_000_000), 1_000_000);
+	assert_eq!((LogCurve::power(0), LogCurve::power(1), LogCurve::power(1_024)), (0, 1, 11));
+	assert_eq!(CappedSqrtCurve::<MaxShare>::max_power(1_000), Some(200));
+	ExtBuilder::build().execute_with(|| {
+		let origin1 = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin1.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		PowerCurve::set(&Curve::Linear);
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin1.clone(), 1_024u128));
+		assert_eq!(QuadraticVoting::voting_power_of(1).own, 1_024);
+		PowerCurve::set(&Curve::Log);
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin2.clone(), 1_024u128));
+		assert_eq!(QuadraticVoting::voting_power_of(2).own, 11);
+		assert_eq!(QuadraticVoting::get_total_voting_power(), 1_024 + 11);
+	});
+	ExtBuilder::build().execute_with(|| {
+		let origin1 = Origin::signed(1);
+		let origin2 = Origin::signed(2);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin1.clone()));
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin2.clone()));
+		PowerCurve::set(&Curve::CappedSqrt);
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin1.clone(), 1_000_000_000_000u128));
+		assert_ok!(QuadraticVoting::reserve_voting_power(origin2.clone(), 40_000_000_000u128));
+		assert_ok!(QuadraticVoting::add_proposal(origin1.clone(), b"whale".to_vec(), None, None));
+		assert_ok!(QuadraticVoting::vote(origin1.clone(), 0, true));
+		assert_ok!(QuadraticVoting::vote(origin2.clone(), 0, false));
+		assert_eq!(QuadraticVoting::get_referendum(0).unwrap().tally, (1_000_000, 200_000));
+		// Uncapped the ayes would have a two thirds majority, but no account counts for more than 20% of the
+		// 1_200_000 turnout before capping. The cap shrinks the turnout, so the whale ends up with 54% of the
+		// final tally
+		let status = &QuadraticVoting::open_referenda_status()[0];
+		assert_eq!((status.ayes, status.nays, status.projected_pass), (240_000, 200_000, false));
+		assert_eq!(QuadraticVoting::projected_outcome(0), Some(false));
+		run_to_block(62);
+		let archived = QuadraticVoting::get_archived_proposal(0).unwrap();
+		assert_eq!((archived.tally, archived.outcome), ((240_000, 200_000), ProposalOutcome::Rejected));
+	});
+}
+
+#[test]
+fn call_proposal_errors() {
+	ExtBuilder::build().execute_with(|| {
+		let origin = Origin::signed(1);
+		assert_ok!(IdentityPallet::try_add_as_social_graph_originator(origin.clone()));
+		assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"garbage".to_vec(), None, Some(vec![255, 255])), Error::<Test>::UndecodableCall);
+		let long_call = Call::System(frame_system::Call::remark { remark: vec![0; 1_000] });
+		assert_noop!(QuadraticVoting::add_proposal(origin.clone(), b"long remark".to_vec(), None, Some(long_call.encode())), Error::<Test>::ProposalCallTooLong);
 	});
 }
\ No newline at end of file
@@ -0,0 +1,429 @@
+//! Weights for quadratic_voting
+//!
+//! PLACEHOLDER ESTIMATES. These values were written by hand from the storage each call touches and have not been
+//! measured. Replace them with real numbers from the benchmarks in `benchmarking.rs`, run on reference hardware,
+//! before relying on them on a live chain, and again whenever the pallet's storage access changes:
+//!
+//! ./target/release/node-template benchmark pallet \
+//!     --chain=dev \
+//!     --steps=50 \
+//!     --repeat=20 \
+//!     --pallet=quadratic_voting \
+//!     --extrinsic='*' \
+//!     --execution=wasm \
+//!     --wasm-execution=compiled \
+//!     --heap-pages=4096 \
+//!     --output=./pallets/quadratic_voting/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for quadratic_voting.
+pub trait WeightInfo {
+	fn add_proposal(b: u32, ) -> Weight;
+	fn note_description(b: u32, ) -> Weight;
+	fn clear_description() -> Weight;
+	fn reserve_voting_power() -> Weight;
+	fn release_all_voting_power() -> Weight;
+	fn release_voting_power() -> Weight;
+	fn withdraw_unbonded() -> Weight;
+	fn vote(d: u32, ) -> Weight;
+	fn vote_with_credits() -> Weight;
+	fn commit_vote() -> Weight;
+	fn reveal_vote(d: u32, ) -> Weight;
+	fn withdraw_proposal(v: u32, ) -> Weight;
+	fn veto_proposal(v: u32, ) -> Weight;
+	fn cancel_enactment() -> Weight;
+	fn enact(b: u32, ) -> Weight;
+	fn delegate(c: u32, ) -> Weight;
+	fn undelegate(c: u32, ) -> Weight;
+	fn on_initialize(r: u32, ) -> Weight;
+	fn finalize_referendum(v: u32, ) -> Weight;
+	fn penalize_unrevealed(c: u32, ) -> Weight;
+	fn prune_archive(a: u32, ) -> Weight;
+}
+
+/// Hand estimated placeholder weights for quadratic_voting, pending a benchmark run on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: IdentityPallet VoterSet (r:1 w:0)
+	// Storage: QuadraticVoting Descriptions (r:1 w:0)
+	// Storage: QuadraticVoting ReferendumCount (r:1 w:1)
+	// Storage: QuadraticVoting OpenReferenda (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: QuadraticVoting Proposals (r:0 w:1)
+	// Storage: QuadraticVoting Referenda (r:0 w:1)
+	// Storage: QuadraticVoting DescriptionRefs (r:1 w:1)
+	fn add_proposal(b: u32, ) -> Weight {
+		(40_135_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: QuadraticVoting Descriptions (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn note_description(b: u32, ) -> Weight {
+		(27_804_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: QuadraticVoting Descriptions (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: QuadraticVoting DescriptionRefs (r:1 w:0)
+	fn clear_description() -> Weight {
+		(26_371_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: QuadraticVoting ReserveSet (r:1 w:1)
+	// Storage: System Account (r:1 w:0)
+	// Storage: QuadraticVoting Unbonding (r:1 w:0)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: QuadraticVoting CounterForReserveSet (r:1 w:1)
+	// Storage: QuadraticVoting TotalVotingPower (r:1 w:1)
+	fn reserve_voting_power() -> Weight {
+		(41_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: QuadraticVoting ReserveSet (r:1 w:1)
+	// Storage: QuadraticVoting VoteLocks (r:1 w:1)
+	// Storage: QuadraticVoting Unbonding (r:1 w:1)
+	// Storage: QuadraticVoting CounterForReserveSet (r:1 w:1)
+	// Storage: QuadraticVoting TotalVotingPower (r:1 w:1)
+	fn release_all_voting_power() -> Weight {
+		(42_613_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: QuadraticVoting ReserveSet (r:1 w:1)
+	// Storage: QuadraticVoting VoteLocks (r:1 w:1)
+	// Storage: QuadraticVoting Unbonding (r:1 w:1)
+	// Storage: QuadraticVoting TotalVotingPower (r:1 w:1)
+	fn release_voting_power() -> Weight {
+		(40_952_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: QuadraticVoting Unbonding (r:1 w:1)
+	// Storage: QuadraticVoting ReserveSet (r:1 w:0)
+	// Storage: Balances Locks (r:1 w:1)
+	fn withdraw_unbonded() -> Weight {
+		(33_410_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: QuadraticVoting ReserveSet (r:1 w:0)
+	// Storage: IdentityPallet VoterSet (r:1 w:0)
+	// Storage: QuadraticVoting Referenda (r:1 w:1)
+	// Storage: QuadraticVoting Votes (r:1 w:1)
+	// Storage: QuadraticVoting VoteLocks (r:1 w:1)
+	// Storage: QuadraticVoting Delegators (r:1 w:0)
+	fn vote(d: u32, ) -> Weight {
+		(44_915_000 as Weight)
+			.saturating_add((21_364_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	// Storage: IdentityPallet VoterSet (r:1 w:0)
+	// Storage: QuadraticVoting Referenda (r:1 w:1)
+	// Storage: QuadraticVoting VoiceCreditsSpent (r:1 w:1)
+	// Storage: QuadraticVoting CreditBallots (r:1 w:1)
+	// Storage: QuadraticVoting ReserveSet (r:1 w:0)
+	// Storage: QuadraticVoting Votes (r:1 w:1)
+	// Storage: QuadraticVoting VoteLocks (r:1 w:1)
+	fn vote_with_credits() -> Weight {
+		(46_127_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: QuadraticVoting ReserveSet (r:1 w:0)
+	// Storage: IdentityPallet VoterSet (r:1 w:0)
+	// Storage: QuadraticVoting Referenda (r:1 w:0)
+	// Storage: QuadraticVoting Commitments (r:0 w:1)
+	// Storage: QuadraticVoting VoteLocks (r:1 w:1)
+	fn commit_vote() -> Weight {
+		(31_448_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: QuadraticVoting Referenda (r:1 w:1)
+	// Storage: QuadraticVoting Commitments (r:1 w:1)
+	// Storage: QuadraticVoting Votes (r:1 w:1)
+	// Storage: QuadraticVoting VoteLocks (r:1 w:1)
+	// Storage: QuadraticVoting Delegators (r:1 w:0)
+	fn reveal_vote(d: u32, ) -> Weight {
+		(47_306_000 as Weight)
+			.saturating_add((21_370_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	// Storage: QuadraticVoting Referenda (r:1 w:1)
+	// Storage: QuadraticVoting OpenReferenda (r:1 w:1)
+	// Storage: QuadraticVoting Votes (r:0 w:1)
+	// Storage: QuadraticVoting Archive (r:0 w:1)
+	// Storage: QuadraticVoting ArchiveExpiry (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: QuadraticVoting Proposals (r:1 w:1)
+	// Storage: QuadraticVoting DescriptionRefs (r:1 w:1)
+	fn withdraw_proposal(v: u32, ) -> Weight {
+		(40_125_000 as Weight)
+			.saturating_add((1_012_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+	}
+	// Storage: QuadraticVoting Referenda (r:1 w:1)
+	// Storage: QuadraticVoting OpenReferenda (r:1 w:1)
+	// Storage: QuadraticVoting Votes (r:0 w:1)
+	// Storage: QuadraticVoting Archive (r:0 w:1)
+	// Storage: QuadraticVoting ArchiveExpiry (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: QuadraticVoting Proposals (r:1 w:1)
+	// Storage: QuadraticVoting DescriptionRefs (r:1 w:1)
+	fn veto_proposal(v: u32, ) -> Weight {
+		(41_530_000 as Weight)
+			.saturating_add((1_009_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+	}
+	// Storage: QuadraticVoting EnactmentBlock (r:1 w:1)
+	// Storage: QuadraticVoting Enactments (r:0 w:1)
+	// Storage: QuadraticVoting Archive (r:1 w:1)
+	// Storage: QuadraticVoting Proposals (r:1 w:1)
+	// Storage: QuadraticVoting DescriptionRefs (r:1 w:1)
+	fn cancel_enactment() -> Weight {
+		(30_582_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: QuadraticVoting Enactments (r:1 w:1)
+	// Storage: QuadraticVoting EnactmentBlock (r:0 w:1)
+	// Storage: QuadraticVoting Archive (r:1 w:1)
+	// Storage: QuadraticVoting Proposals (r:1 w:0)
+	// Storage: QuadraticVoting DescriptionRefs (r:1 w:1)
+	fn enact(b: u32, ) -> Weight {
+		(21_946_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: IdentityPallet VoterSet (r:2 w:0)
+	// Storage: QuadraticVoting Delegations (r:1 w:1)
+	// Storage: QuadraticVoting Delegators (r:2 w:2)
+	// Storage: QuadraticVoting DelegatedCount (r:1 w:0)
+	// Storage: QuadraticVoting Delegations (r:c w:0) on the old and new chains
+	// Storage: QuadraticVoting DelegatedCount (r:c w:c) on the old and new chains
+	fn delegate(c: u32, ) -> Weight {
+		(33_790_000 as Weight)
+			.saturating_add((6_208_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: QuadraticVoting Delegations (r:1 w:1)
+	// Storage: QuadraticVoting Delegators (r:1 w:1)
+	// Storage: QuadraticVoting DelegatedCount (r:1 w:0)
+	// Storage: QuadraticVoting Delegations (r:c w:0)
+	// Storage: QuadraticVoting DelegatedCount (r:c w:c)
+	fn undelegate(c: u32, ) -> Weight {
+		(24_661_000 as Weight)
+			.saturating_add((3_104_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: QuadraticVoting OpenReferenda (r:1 w:0)
+	// Storage: QuadraticVoting ArchiveExpiry (r:1 w:0)
+	fn on_initialize(r: u32, ) -> Weight {
+		(4_102_000 as Weight)
+			.saturating_add((188_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	// Storage: QuadraticVoting Referenda (r:1 w:1)
+	// Storage: QuadraticVoting OpenReferenda (r:1 w:1)
+	// Storage: QuadraticVoting TotalVotingPower (r:1 w:0)
+	// Storage: IdentityPallet CounterForVoterSet (r:1 w:0)
+	// Storage: QuadraticVoting Votes (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: QuadraticVoting Archive (r:0 w:1)
+	// Storage: QuadraticVoting ArchiveExpiry (r:0 w:1)
+	// Storage: QuadraticVoting DescriptionRefs (r:1 w:1)
+	fn finalize_referendum(v: u32, ) -> Weight {
+		(52_318_000 as Weight)
+			.saturating_add((1_015_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+	}
+	// Storage: QuadraticVoting Commitments (r:1 w:1)
+	// Storage: QuadraticVoting ReserveSet (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: QuadraticVoting TotalVotingPower (r:1 w:1)
+	fn penalize_unrevealed(c: u32, ) -> Weight {
+		(3_214_000 as Weight)
+			.saturating_add((33_902_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: QuadraticVoting ArchiveExpiry (r:1 w:1)
+	// Storage: QuadraticVoting Archive (r:0 w:1)
+	// Storage: QuadraticVoting Proposals (r:1 w:1)
+	// Storage: QuadraticVoting EnactmentBlock (r:1 w:0)
+	// Storage: QuadraticVoting DescriptionRefs (r:1 w:1)
+	fn prune_archive(a: u32, ) -> Weight {
+		(3_870_000 as Weight)
+			.saturating_add((2_417_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn add_proposal(b: u32, ) -> Weight {
+		(40_135_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn note_description(b: u32, ) -> Weight {
+		(27_804_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn clear_description() -> Weight {
+		(26_371_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn reserve_voting_power() -> Weight {
+		(41_207_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn release_all_voting_power() -> Weight {
+		(42_613_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn release_voting_power() -> Weight {
+		(40_952_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn withdraw_unbonded() -> Weight {
+		(33_410_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn vote(d: u32, ) -> Weight {
+		(44_915_000 as Weight)
+			.saturating_add((21_364_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	fn vote_with_credits() -> Weight {
+		(46_127_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn commit_vote() -> Weight {
+		(31_448_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn reveal_vote(d: u32, ) -> Weight {
+		(47_306_000 as Weight)
+			.saturating_add((21_370_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(d as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(d as Weight)))
+	}
+	fn withdraw_proposal(v: u32, ) -> Weight {
+		(40_125_000 as Weight)
+			.saturating_add((1_012_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+	}
+	fn veto_proposal(v: u32, ) -> Weight {
+		(41_530_000 as Weight)
+			.saturating_add((1_009_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+	}
+	fn cancel_enactment() -> Weight {
+		(30_582_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn enact(b: u32, ) -> Weight {
+		(21_946_000 as Weight)
+			.saturating_add((1_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn delegate(c: u32, ) -> Weight {
+		(33_790_000 as Weight)
+			.saturating_add((6_208_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	fn undelegate(c: u32, ) -> Weight {
+		(24_661_000 as Weight)
+			.saturating_add((3_104_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+	fn on_initialize(r: u32, ) -> Weight {
+		(4_102_000 as Weight)
+			.saturating_add((188_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+	}
+	fn finalize_referendum(v: u32, ) -> Weight {
+		(52_318_000 as Weight)
+			.saturating_add((1_015_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+	}
+	fn penalize_unrevealed(c: u32, ) -> Weight {
+		(3_214_000 as Weight)
+			.saturating_add((33_902_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(c as Weight)))
+	}
+	fn prune_archive(a: u32, ) -> Weight {
+		(3_870_000 as Weight)
+			.saturating_add((2_417_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+}
@@ -27,13 +27,13 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
@@ -46,6 +46,7 @@ pub use sp_runtime::{Perbill, Permill};
 /// Import the template pallet.
 pub use identity_pallet;
 pub use quadratic_voting;
+pub use quadratic_funding;
 pub use crypto_kitties;
 pub use brads_soft_coupling;
 
@@ -102,12 +103,12 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// The version of the runtime specification. A full node will not attempt to use its native
 	//   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
-	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
-	//   the compatible custom types.
-	spec_version: 100,
+	// This value started at 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
+	//   the compatible custom types. Bump it with every runtime upgrade so storage migrations run.
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -266,9 +267,23 @@ impl pallet_sudo::Config for Runtime {
 }
 
 /// Configure the pallet-template in pallets/template.
+parameter_types! {
+	pub VouchBondSlash: Perbill = Perbill::from_percent(50);
+}
+
 impl identity_pallet::Config for Runtime {
 	type Event = Event;
 	type MinVouches = ConstU32<2>;
+	type RemoveOrigin = frame_system::EnsureRoot<AccountId>;
+	type MembershipPeriod = ConstU32<{ 180 * DAYS }>;
+	type ReattestationVouches = ConstU32<0>;
+	type Currency = Balances;
+	type VouchBond = ConstU128<1_000>;
+	type BondMaturity = ConstU32<{ 30 * DAYS }>;
+	type BondSlash = VouchBondSlash;
+	type Slash = ();
+	type OnMemberRemoved = QuadraticVoting;
+	type MaxVouchees = ConstU32<100>;
 }
 
 impl crypto_kitties::Config for Runtime {
@@ -278,15 +293,76 @@ impl crypto_kitties::Config for Runtime {
     type MaxKittiesOwned = frame_support::pallet_prelude::ConstU32<1>;
 }
 
+parameter_types! {
+	pub TwoThirds: Perbill = Perbill::from_rational(2u32, 3u32);
+	pub OneThird: Perbill = Perbill::from_rational(1u32, 3u32);
+	pub UnrevealedPenalty: Perbill = Perbill::from_percent(10);
+	pub const QuadraticVotingMode: quadratic_voting::VotingMode = quadratic_voting::VotingMode::SqrtOfReserve;
+	pub const VoiceCredits: quadratic_voting::CreditSource = quadratic_voting::CreditSource::Reserve;
+}
+
 impl quadratic_voting::Config for Runtime {
 	type Event = Event;
 	type Token = Balances;
+	type Call = Call;
+	type ExecutionOrigin = quadratic_voting::DispatchAsRoot<Runtime>;
 	type MaxProposals = ConstU32<25>;
 	type MaxProposalLength = ConstU32<1000>;
 	type BlocksPerVote = ConstU32<60>;
-	type ParticipationThreshold = ConstU128<10_000>;
+	type ApprovalRule = quadratic_voting::SuperMajority<TwoThirds>;
+	type QuorumRule = quadratic_voting::AbsolutePower<ConstU128<10_000>>;
+	type VotingPowerCurve = quadratic_voting::SqrtCurve;
 	type Identity = IdentityPallet;
-	type Kitties = SubstrateKitties;
+	type MaxDelegators = ConstU32<10>;
+	type MaxDelegationDepth = ConstU32<5>;
+	type VetoOrigin = frame_system::EnsureRoot<AccountId>;
+	type EnactmentPeriod = ConstU32<{ 2 * DAYS }>;
+	type ProposalDeposit = ConstU128<1_000>;
+	type MaxDescriptionLength = ConstU32<16_384>;
+	type DescriptionBaseDeposit = ConstU128<100>;
+	type DescriptionByteDeposit = ConstU128<1>;
+	type DepositRefundThreshold = OneThird;
+	type Slash = ();
+	type ArchiveRetention = ConstU32<{ 30 * DAYS }>;
+	type SecretBallots = ConstBool<false>;
+	type UnrevealedPenalty = UnrevealedPenalty;
+	type Mode = QuadraticVotingMode;
+	type VoiceCredits = VoiceCredits;
+	type CreditRoundLength = ConstU32<{ 7 * DAYS }>;
+	type UnbondingPeriod = ConstU32<{ 7 * DAYS }>;
+	type MaxUnbondingChunks = ConstU32<16>;
+	type MaxVoters = ConstU32<512>;
+	type WeightInfo = quadratic_voting::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = QuadraticVotingBenchmarkHelper;
+}
+
+/// Adds the quadratic voting benchmarks' accounts to the voter set through the identity pallet
+#[cfg(feature = "runtime-benchmarks")]
+pub struct QuadraticVotingBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl quadratic_voting::BenchmarkHelper<AccountId> for QuadraticVotingBenchmarkHelper {
+	fn add_member(who: &AccountId) {
+		IdentityPallet::force_add_member(Origin::root(), who.clone()).expect("Benchmark accounts aren't members yet");
+	}
+}
+
+parameter_types! {
+	pub const QuadraticFundingPalletId: PalletId = PalletId(*b"py/qfund");
+}
+
+impl quadratic_funding::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Identity = IdentityPallet;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type PalletId = QuadraticFundingPalletId;
+	type RoundLength = ConstU32<{ 14 * DAYS }>;
+	type MaxProjects = ConstU32<50>;
+	type MaxNameLength = ConstU32<64>;
+	type MaxContributors = ConstU32<200>;
+	type WeightInfo = quadratic_funding::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -308,6 +384,7 @@ construct_runtime!(
 		IdentityPallet: identity_pallet,
 		SubstrateKitties: crypto_kitties,
 		QuadraticVoting: quadratic_voting,
+		QuadraticFunding: quadratic_funding,
 	}
 );
 
@@ -353,6 +430,7 @@ mod benches {
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[identity_pallet, IdentityPallet]
+		[quadratic_voting, QuadraticVoting]
 	);
 }
 
@@ -487,6 +565,46 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl quadratic_voting_rpc_runtime_api::QuadraticVotingApi<Block, AccountId, BlockNumber> for Runtime {
+		fn open_referenda() -> Vec<quadratic_voting::ReferendumStatus<AccountId, BlockNumber>> {
+			QuadraticVoting::open_referenda_status()
+		}
+
+		fn projected_outcome(index: quadratic_voting::ProposalIndex) -> Option<bool> {
+			QuadraticVoting::projected_outcome(index)
+		}
+
+		fn voting_power(who: AccountId) -> quadratic_voting::VotingPowerInfo {
+			QuadraticVoting::voting_power_of(who)
+		}
+
+		fn blocks_until_next_finalization() -> Option<BlockNumber> {
+			QuadraticVoting::blocks_until_next_finalization()
+		}
+	}
+
+	impl identity_pallet_rpc_runtime_api::IdentityGraphApi<Block, AccountId, BlockNumber> for Runtime {
+		fn member(who: AccountId) -> Option<identity_pallet::MemberRecord<BlockNumber>> {
+			IdentityPallet::get_member_record(who)
+		}
+
+		fn vouchers(who: AccountId) -> Vec<AccountId> {
+			IdentityPallet::vouchers_of(&who)
+		}
+
+		fn vouchees(who: AccountId) -> Vec<AccountId> {
+			IdentityPallet::vouchees_of(&who)
+		}
+
+		fn path_to_originator(who: AccountId) -> Option<Vec<AccountId>> {
+			IdentityPallet::path_to_originator(&who)
+		}
+
+		fn member_depths() -> Vec<(AccountId, u32)> {
+			IdentityPallet::member_depths()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (